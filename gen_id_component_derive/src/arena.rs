@@ -0,0 +1,258 @@
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote};
+use syn::spanned::Spanned;
+use syn::{
+    Attribute, Data, DeriveInput, Error, Fields, GenericArgument, Ident, Meta, NestedMeta,
+    PathArguments, Type, Visibility,
+};
+
+#[derive(Copy, Clone, PartialEq)]
+enum Kind {
+    Component,
+    RawComponent,
+}
+
+struct Column {
+    vis: Visibility,
+    name: Ident,
+    kind: Kind,
+    arena: Type,
+    value: Type,
+}
+
+pub fn expand(input: DeriveInput) -> syn::Result<TokenStream> {
+    if !input.generics.params.is_empty() {
+        return Err(Error::new(
+            input.generics.span(),
+            "Arena cannot be derived for generic structs",
+        ));
+    }
+
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => return Err(Error::new(Span::call_site(), "expected named fields")),
+        },
+        _ => return Err(Error::new(Span::call_site(), "expected a struct")),
+    };
+
+    let columns = fields
+        .iter()
+        .map(|field| {
+            let (kind, arena, value) = parse_column(&field.ty)?;
+            Ok(Column {
+                vis: field.vis.clone(),
+                name: field.ident.clone().expect("named field"),
+                kind,
+                arena,
+                value,
+            })
+        })
+        .collect::<syn::Result<Vec<_>>>()?;
+
+    let arena = match columns.first() {
+        Some(column) => column.arena.clone(),
+        None => {
+            return Err(Error::new(
+                Span::call_site(),
+                "expected at least one column",
+            ))
+        }
+    };
+
+    let expected = quote!(#arena).to_string();
+    for column in &columns[1..] {
+        let found = &column.arena;
+        if quote!(#found).to_string() != expected {
+            return Err(Error::new(
+                found.span(),
+                format!("expected a column over `{}`", expected),
+            ));
+        }
+    }
+
+    let clone = parse_clone(&input.attrs)?;
+
+    let name = &input.ident;
+    let vis = &input.vis;
+    let row = format_ident!("{}Row", name);
+    let names = columns.iter().map(|c| &c.name).collect::<Vec<_>>();
+    let visibilities = columns.iter().map(|c| &c.vis);
+    let values = columns.iter().map(|c| &c.value);
+    let labels = names.iter().map(|n| n.to_string());
+    let first = names[0];
+
+    let id = |column: &Column| match column.kind {
+        Kind::Component => quote!(id),
+        Kind::RawComponent => quote!(::gen_id_component::__private::ValidId::id(&id)),
+    };
+    let ids = columns.iter().map(id).collect::<Vec<_>>();
+
+    // The bounds are higher-ranked so that they are checked where the method is used rather than
+    // where it is defined, which lets the derive accept columns that are not `Clone` or `Default`.
+    let clone_bounds = columns.iter().map(|c| {
+        let value = &c.value;
+        quote!(for<'clone> #value: Clone)
+    });
+    let default_bounds = columns.iter().map(|c| {
+        let value = &c.value;
+        quote!(for<'default> #value: Default)
+    });
+
+    let clone_impl = if clone {
+        quote! {
+            impl Clone for #name {
+                #[inline]
+                fn clone(&self) -> Self {
+                    Self {
+                        #(#names: self.#names.clone(),)*
+                    }
+                }
+
+                #[inline]
+                fn clone_from(&mut self, source: &Self) {
+                    #(self.#names.clone_from(&source.#names);)*
+                }
+            }
+        }
+    } else {
+        quote!()
+    };
+
+    let swap = columns.iter().map(|column| {
+        let name = &column.name;
+        match column.kind {
+            Kind::Component => quote!(self.#name.swap(a, b);),
+            Kind::RawComponent => quote! {
+                self.#name.swap(
+                    ::gen_id_component::__private::ValidId::id(&a),
+                    ::gen_id_component::__private::ValidId::id(&b),
+                );
+            },
+        }
+    });
+
+    Ok(quote! {
+        #vis struct #row {
+            #(#visibilities #names: #values,)*
+        }
+
+        impl #name {
+            /// Inserts a value into every column.
+            pub fn insert_row<__GidId>(&mut self, id: __GidId, row: #row)
+            where
+                __GidId: ::gen_id_component::__private::ValidId<Arena = #arena> + Copy,
+            {
+                #(self.#names.insert(#ids, row.#names);)*
+            }
+
+            /// Clones the value from every column, or returns `None` if any column is missing a value.
+            pub fn get_row<__GidId>(&self, id: __GidId) -> Option<#row>
+            where
+                __GidId: ::gen_id_component::__private::ValidId<Arena = #arena> + Copy,
+                #(#clone_bounds,)*
+            {
+                Some(#row {
+                    #(#names: self.#names.get(#ids)?.clone(),)*
+                })
+            }
+
+            /// Takes the value from every column, leaving default values in their place.
+            pub fn remove_row<__GidId>(&mut self, id: __GidId) -> Option<#row>
+            where
+                __GidId: ::gen_id_component::__private::ValidId<Arena = #arena> + Copy,
+                #(#default_bounds,)*
+            {
+                #(self.#names.get(#ids)?;)*
+
+                Some(#row {
+                    #(#names: std::mem::take(self.#names.get_mut(#ids)?),)*
+                })
+            }
+
+            /// Swaps the values for two ids in every column.
+            pub fn swap<__GidIdA, __GidIdB>(&mut self, a: __GidIdA, b: __GidIdB)
+            where
+                __GidIdA: ::gen_id_component::__private::ValidId<Arena = #arena> + Copy,
+                __GidIdB: ::gen_id_component::__private::ValidId<Arena = #arena> + Copy,
+            {
+                #(#swap)*
+            }
+
+            /// Returns the shared length of the columns, or the first column whose length differs.
+            pub fn validate(&self) -> Result<usize, ::gen_id_component::LengthMismatch> {
+                let expected = self.#first.len();
+
+                #(
+                    if self.#names.len() != expected {
+                        return Err(::gen_id_component::LengthMismatch {
+                            column: #labels,
+                            len: self.#names.len(),
+                            expected,
+                        });
+                    }
+                )*
+
+                Ok(expected)
+            }
+        }
+
+        #clone_impl
+    })
+}
+
+/// Whether the struct asks for a column-wise `Clone` impl with `#[arena(clone)]`.
+fn parse_clone(attrs: &[Attribute]) -> syn::Result<bool> {
+    let mut clone = false;
+
+    for attr in attrs.iter().filter(|attr| attr.path.is_ident("arena")) {
+        let list = match attr.parse_meta()? {
+            Meta::List(list) => list,
+            meta => return Err(Error::new(meta.span(), "expected `#[arena(clone)]`")),
+        };
+
+        for nested in list.nested {
+            match nested {
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("clone") => clone = true,
+                nested => return Err(Error::new(nested.span(), "expected `clone`")),
+            }
+        }
+    }
+
+    Ok(clone)
+}
+
+fn parse_column(ty: &Type) -> syn::Result<(Kind, Type, Type)> {
+    let error = || {
+        Error::new(
+            ty.span(),
+            "expected `Component<Arena, T>` or `RawComponent<Arena, T>`",
+        )
+    };
+
+    let segment = match ty {
+        Type::Path(path) => path.path.segments.last().ok_or_else(error)?,
+        _ => return Err(error()),
+    };
+
+    let kind = match segment.ident.to_string().as_str() {
+        "Component" => Kind::Component,
+        "RawComponent" => Kind::RawComponent,
+        _ => return Err(error()),
+    };
+
+    let args = match &segment.arguments {
+        PathArguments::AngleBracketed(args) => &args.args,
+        _ => return Err(error()),
+    };
+
+    let mut types = args.iter().filter_map(|arg| match arg {
+        GenericArgument::Type(ty) => Some(ty.clone()),
+        _ => None,
+    });
+
+    match (types.next(), types.next(), types.next()) {
+        (Some(arena), Some(value), None) => Ok((kind, arena, value)),
+        _ => Err(error()),
+    }
+}
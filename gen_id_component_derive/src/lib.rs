@@ -0,0 +1,29 @@
+use proc_macro::TokenStream;
+use syn::{parse_macro_input, DeriveInput};
+
+mod arena;
+mod soa;
+
+/// Generates a row type and row-wise operations for a struct whose fields are all
+/// `Component<Arena, _>` or `RawComponent<Arena, _>` over the same arena.
+///
+/// `#[arena(clone)]` also generates a `Clone` impl that forwards `clone_from` to every column.
+#[proc_macro_derive(Arena, attributes(arena))]
+pub fn derive_arena(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    arena::expand(input)
+        .unwrap_or_else(|error| error.to_compile_error())
+        .into()
+}
+
+/// Generates `{Name}Component<Arena>`, which stores each field of the struct in its own
+/// `Component` so that fields can be updated column-wise.
+#[proc_macro_derive(StructOfArrays)]
+pub fn derive_struct_of_arrays(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    soa::expand(input)
+        .unwrap_or_else(|error| error.to_compile_error())
+        .into()
+}
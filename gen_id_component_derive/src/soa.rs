@@ -0,0 +1,130 @@
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote};
+use syn::spanned::Spanned;
+use syn::{Data, DeriveInput, Error, Fields};
+
+pub fn expand(input: DeriveInput) -> syn::Result<TokenStream> {
+    if !input.generics.params.is_empty() {
+        return Err(Error::new(
+            input.generics.span(),
+            "StructOfArrays cannot be derived for generic structs",
+        ));
+    }
+
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => return Err(Error::new(Span::call_site(), "expected named fields")),
+        },
+        _ => return Err(Error::new(Span::call_site(), "expected a struct")),
+    };
+
+    let first = match fields.first() {
+        Some(field) => &field.ident,
+        None => return Err(Error::new(Span::call_site(), "expected at least one field")),
+    };
+
+    let name = &input.ident;
+    let vis = &input.vis;
+    let component = format_ident!("{}Component", name);
+    let names = fields.iter().map(|f| &f.ident).collect::<Vec<_>>();
+    let visibilities = fields.iter().map(|f| &f.vis);
+    let types = fields.iter().map(|f| &f.ty).collect::<Vec<_>>();
+    // Higher-ranked so that the bound is checked where `get` is used rather than where it is
+    // defined, which lets the derive accept fields that are not `Clone`.
+    let clone_bounds = types.iter().map(|ty| quote!(for<'clone> #ty: Clone));
+    // The generic parameters have unusual names so that they cannot shadow types used by the fields.
+    let vecs = names
+        .iter()
+        .map(|n| format_ident!("{}_values", n.as_ref().expect("named field")))
+        .collect::<Vec<_>>();
+
+    Ok(quote! {
+        /// Column-wise storage with one component per field.
+        #vis struct #component<__GidArena> {
+            #(#visibilities #names: ::gen_id_component::Component<__GidArena, #types>,)*
+        }
+
+        impl<__GidArena> Default for #component<__GidArena> {
+            #[inline]
+            fn default() -> Self {
+                Self {
+                    #(#names: Default::default(),)*
+                }
+            }
+        }
+
+        impl<__GidArena> Clone for #component<__GidArena>
+        where
+            #(::gen_id_component::Component<__GidArena, #types>: Clone,)*
+        {
+            #[inline]
+            fn clone(&self) -> Self {
+                Self {
+                    #(#names: self.#names.clone(),)*
+                }
+            }
+
+            #[inline]
+            fn clone_from(&mut self, source: &Self) {
+                #(self.#names.clone_from(&source.#names);)*
+            }
+        }
+
+        impl<__GidArena> #component<__GidArena> {
+            /// Splits the value into its fields and inserts each into its column.
+            #[inline]
+            pub fn insert<__GidId>(&mut self, id: __GidId, value: #name)
+            where
+                __GidId: ::gen_id_component::__private::ValidId<Arena = __GidArena> + Copy,
+            {
+                #(self.#names.insert(id, value.#names);)*
+            }
+
+            /// Reassembles the value from each column.
+            #[inline]
+            pub fn get<__GidId>(&self, id: __GidId) -> Option<#name>
+            where
+                __GidId: ::gen_id_component::__private::ValidId<Arena = __GidArena> + Copy,
+                #(#clone_bounds,)*
+            {
+                Some(#name {
+                    #(#names: self.#names.get(id)?.clone(),)*
+                })
+            }
+
+            #[inline]
+            pub fn swap<__GidIdA, __GidIdB>(&mut self, a: __GidIdA, b: __GidIdB)
+            where
+                __GidIdA: ::gen_id_component::__private::ValidId<Arena = __GidArena> + Copy,
+                __GidIdB: ::gen_id_component::__private::ValidId<Arena = __GidArena> + Copy,
+            {
+                #(self.#names.swap(a, b);)*
+            }
+
+            #[inline]
+            pub fn len(&self) -> usize {
+                self.#first.len()
+            }
+
+            #[inline]
+            pub fn is_empty(&self) -> bool {
+                self.len() == 0
+            }
+        }
+
+        impl<__GidArena> From<Vec<#name>> for #component<__GidArena> {
+            fn from(values: Vec<#name>) -> Self {
+                #(let mut #vecs = Vec::with_capacity(values.len());)*
+
+                for value in values {
+                    #(#vecs.push(value.#names);)*
+                }
+
+                Self {
+                    #(#names: #vecs.into(),)*
+                }
+            }
+        }
+    })
+}
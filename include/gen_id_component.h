@@ -0,0 +1,118 @@
+/* Generated by gen_id_component::ffi::c_header. Do not edit. */
+
+#ifndef GEN_ID_COMPONENT_H
+#define GEN_ID_COMPONENT_H
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef struct gid_component_u8 gid_component_u8;
+
+gid_component_u8 *gid_component_u8_new(void);
+void gid_component_u8_free(gid_component_u8 *component);
+size_t gid_component_u8_len(const gid_component_u8 *component);
+uint8_t *gid_component_u8_data(gid_component_u8 *component);
+bool gid_component_u8_insert(gid_component_u8 *component, size_t index, uint8_t value);
+void gid_component_u8_copy_from(gid_component_u8 *component, const uint8_t *source, size_t len);
+size_t gid_component_u8_copy_to(const gid_component_u8 *component, uint8_t *target, size_t capacity);
+
+typedef struct gid_component_u16 gid_component_u16;
+
+gid_component_u16 *gid_component_u16_new(void);
+void gid_component_u16_free(gid_component_u16 *component);
+size_t gid_component_u16_len(const gid_component_u16 *component);
+uint16_t *gid_component_u16_data(gid_component_u16 *component);
+bool gid_component_u16_insert(gid_component_u16 *component, size_t index, uint16_t value);
+void gid_component_u16_copy_from(gid_component_u16 *component, const uint16_t *source, size_t len);
+size_t gid_component_u16_copy_to(const gid_component_u16 *component, uint16_t *target, size_t capacity);
+
+typedef struct gid_component_u32 gid_component_u32;
+
+gid_component_u32 *gid_component_u32_new(void);
+void gid_component_u32_free(gid_component_u32 *component);
+size_t gid_component_u32_len(const gid_component_u32 *component);
+uint32_t *gid_component_u32_data(gid_component_u32 *component);
+bool gid_component_u32_insert(gid_component_u32 *component, size_t index, uint32_t value);
+void gid_component_u32_copy_from(gid_component_u32 *component, const uint32_t *source, size_t len);
+size_t gid_component_u32_copy_to(const gid_component_u32 *component, uint32_t *target, size_t capacity);
+
+typedef struct gid_component_u64 gid_component_u64;
+
+gid_component_u64 *gid_component_u64_new(void);
+void gid_component_u64_free(gid_component_u64 *component);
+size_t gid_component_u64_len(const gid_component_u64 *component);
+uint64_t *gid_component_u64_data(gid_component_u64 *component);
+bool gid_component_u64_insert(gid_component_u64 *component, size_t index, uint64_t value);
+void gid_component_u64_copy_from(gid_component_u64 *component, const uint64_t *source, size_t len);
+size_t gid_component_u64_copy_to(const gid_component_u64 *component, uint64_t *target, size_t capacity);
+
+typedef struct gid_component_i8 gid_component_i8;
+
+gid_component_i8 *gid_component_i8_new(void);
+void gid_component_i8_free(gid_component_i8 *component);
+size_t gid_component_i8_len(const gid_component_i8 *component);
+int8_t *gid_component_i8_data(gid_component_i8 *component);
+bool gid_component_i8_insert(gid_component_i8 *component, size_t index, int8_t value);
+void gid_component_i8_copy_from(gid_component_i8 *component, const int8_t *source, size_t len);
+size_t gid_component_i8_copy_to(const gid_component_i8 *component, int8_t *target, size_t capacity);
+
+typedef struct gid_component_i16 gid_component_i16;
+
+gid_component_i16 *gid_component_i16_new(void);
+void gid_component_i16_free(gid_component_i16 *component);
+size_t gid_component_i16_len(const gid_component_i16 *component);
+int16_t *gid_component_i16_data(gid_component_i16 *component);
+bool gid_component_i16_insert(gid_component_i16 *component, size_t index, int16_t value);
+void gid_component_i16_copy_from(gid_component_i16 *component, const int16_t *source, size_t len);
+size_t gid_component_i16_copy_to(const gid_component_i16 *component, int16_t *target, size_t capacity);
+
+typedef struct gid_component_i32 gid_component_i32;
+
+gid_component_i32 *gid_component_i32_new(void);
+void gid_component_i32_free(gid_component_i32 *component);
+size_t gid_component_i32_len(const gid_component_i32 *component);
+int32_t *gid_component_i32_data(gid_component_i32 *component);
+bool gid_component_i32_insert(gid_component_i32 *component, size_t index, int32_t value);
+void gid_component_i32_copy_from(gid_component_i32 *component, const int32_t *source, size_t len);
+size_t gid_component_i32_copy_to(const gid_component_i32 *component, int32_t *target, size_t capacity);
+
+typedef struct gid_component_i64 gid_component_i64;
+
+gid_component_i64 *gid_component_i64_new(void);
+void gid_component_i64_free(gid_component_i64 *component);
+size_t gid_component_i64_len(const gid_component_i64 *component);
+int64_t *gid_component_i64_data(gid_component_i64 *component);
+bool gid_component_i64_insert(gid_component_i64 *component, size_t index, int64_t value);
+void gid_component_i64_copy_from(gid_component_i64 *component, const int64_t *source, size_t len);
+size_t gid_component_i64_copy_to(const gid_component_i64 *component, int64_t *target, size_t capacity);
+
+typedef struct gid_component_f32 gid_component_f32;
+
+gid_component_f32 *gid_component_f32_new(void);
+void gid_component_f32_free(gid_component_f32 *component);
+size_t gid_component_f32_len(const gid_component_f32 *component);
+float *gid_component_f32_data(gid_component_f32 *component);
+bool gid_component_f32_insert(gid_component_f32 *component, size_t index, float value);
+void gid_component_f32_copy_from(gid_component_f32 *component, const float *source, size_t len);
+size_t gid_component_f32_copy_to(const gid_component_f32 *component, float *target, size_t capacity);
+
+typedef struct gid_component_f64 gid_component_f64;
+
+gid_component_f64 *gid_component_f64_new(void);
+void gid_component_f64_free(gid_component_f64 *component);
+size_t gid_component_f64_len(const gid_component_f64 *component);
+double *gid_component_f64_data(gid_component_f64 *component);
+bool gid_component_f64_insert(gid_component_f64 *component, size_t index, double value);
+void gid_component_f64_copy_from(gid_component_f64 *component, const double *source, size_t len);
+size_t gid_component_f64_copy_to(const gid_component_f64 *component, double *target, size_t capacity);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
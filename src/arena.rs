@@ -0,0 +1,262 @@
+use std::fmt::{Display, Formatter};
+
+/// Returned when the columns of an arena struct do not all have the same length.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LengthMismatch {
+    pub column: &'static str,
+    pub len: usize,
+    pub expected: usize,
+}
+
+impl Display for LengthMismatch {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "column `{}` has length {}, expected {}",
+            self.column, self.len, self.expected
+        )
+    }
+}
+
+impl std::error::Error for LengthMismatch {}
+
+#[cfg(all(test, feature = "derive"))]
+mod test {
+    use crate::test_util::valid;
+    use crate::{Arena, Component, RawComponent, StructOfArrays};
+    use gen_id_allocator::Id;
+
+    #[derive(Debug)]
+    struct Ship;
+
+    #[derive(Debug, Default, Arena)]
+    struct Ships {
+        position: Component<Ship, (f32, f32)>,
+        name: RawComponent<Ship, String>,
+    }
+
+    fn ships() -> Ships {
+        let mut ships = Ships::default();
+        ships.insert_row(
+            valid(0),
+            ShipsRow {
+                position: (1.0, 2.0),
+                name: "Alpha".to_string(),
+            },
+        );
+        ships.insert_row(
+            valid(1),
+            ShipsRow {
+                position: (3.0, 4.0),
+                name: "Beta".to_string(),
+            },
+        );
+        ships
+    }
+
+    #[test]
+    fn rows_round_trip() {
+        let mut ships = ships();
+
+        ships.swap(valid(0), valid(1));
+        let row = ships.remove_row(valid(0)).unwrap();
+
+        assert_eq!((3.0, 4.0), row.position);
+        assert_eq!("Beta", row.name);
+        assert_eq!(Some(String::new()), ships.get_row(valid(0)).map(|r| r.name));
+        assert_eq!(Ok(2), ships.validate());
+    }
+
+    #[test]
+    fn validate_reports_short_column() {
+        let mut ships = ships();
+        ships.name = RawComponent::default();
+
+        let error = ships.validate().unwrap_err();
+
+        assert_eq!("name", error.column);
+        assert_eq!(0, error.len);
+        assert_eq!(2, error.expected);
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct Handle(u32);
+
+    #[derive(Default, Arena)]
+    struct Handles {
+        handle: RawComponent<Ship, Handle>,
+        position: Component<Ship, f32>,
+    }
+
+    #[test]
+    fn columns_need_not_be_clone_or_default() {
+        let mut handles = Handles::default();
+
+        handles.insert_row(
+            valid(0),
+            HandlesRow {
+                handle: Handle(7),
+                position: 1.0,
+            },
+        );
+
+        assert_eq!(Some(&Handle(7)), handles.handle.get(Id::first(0)));
+        assert_eq!(Ok(1), handles.validate());
+    }
+
+    #[derive(Debug)]
+    struct Planet;
+
+    #[derive(Default, Arena)]
+    struct Orbits {
+        orbit: Component<Ship, Option<Id<Planet>>>,
+        escort: RawComponent<Ship, Id<Ship>>,
+    }
+
+    #[test]
+    fn columns_can_hold_ids() {
+        let mut orbits = Orbits::default();
+
+        orbits.insert_row(
+            valid(0),
+            OrbitsRow {
+                orbit: Some(Id::first(3)),
+                escort: Id::first(1),
+            },
+        );
+        let row = orbits.get_row(valid(0)).unwrap();
+
+        assert_eq!(Some(Id::first(3)), row.orbit);
+        assert_eq!(Id::first(1), row.escort);
+    }
+
+    #[derive(Default, Arena)]
+    #[arena(clone)]
+    struct Cloned {
+        position: Component<Ship, f32>,
+    }
+
+    #[derive(Clone, Default, Arena)]
+    struct UserCloned {
+        position: Component<Ship, f32>,
+    }
+
+    #[test]
+    fn clone_is_opt_in() {
+        let mut cloned = Cloned::default();
+        cloned.insert_row(valid(0), ClonedRow { position: 1.0 });
+        let mut user_cloned = UserCloned::default();
+        user_cloned.insert_row(valid(0), UserClonedRow { position: 2.0 });
+
+        let mut target = Cloned::default();
+        target.clone_from(&cloned);
+
+        assert_eq!(Some(1.0), target.get_row(valid(0)).map(|r| r.position));
+        assert_eq!(
+            Some(2.0),
+            user_cloned.clone().get_row(valid(0)).map(|r| r.position)
+        );
+    }
+
+    #[derive(Debug, Clone, PartialEq, StructOfArrays)]
+    struct Kinematics {
+        position: f32,
+        velocity: f32,
+    }
+
+    #[test]
+    fn struct_of_arrays_columns() {
+        let mut kinematics = KinematicsComponent::<Ship>::from(vec![
+            Kinematics {
+                position: 0.0,
+                velocity: 1.0,
+            },
+            Kinematics {
+                position: 5.0,
+                velocity: -1.0,
+            },
+        ]);
+
+        kinematics.position += &kinematics.velocity;
+        kinematics.insert(
+            valid(2),
+            Kinematics {
+                position: 9.0,
+                velocity: 0.0,
+            },
+        );
+
+        assert_eq!(
+            Some(Kinematics {
+                position: 4.0,
+                velocity: -1.0
+            }),
+            kinematics.get(valid(1))
+        );
+        assert_eq!(3, kinematics.len());
+    }
+
+    #[derive(StructOfArrays)]
+    struct Owned {
+        handle: Handle,
+        mass: f32,
+    }
+
+    #[test]
+    fn struct_of_arrays_fields_need_not_be_clone() {
+        let mut owned = OwnedComponent::<Ship>::default();
+
+        owned.insert(
+            valid(0),
+            Owned {
+                handle: Handle(3),
+                mass: 2.0,
+            },
+        );
+
+        assert_eq!(Some(&Handle(3)), owned.handle.get(valid(0)));
+        assert_eq!(1, owned.len());
+    }
+
+    #[derive(Debug, Clone, PartialEq, StructOfArrays)]
+    struct Link {
+        target: Id<Planet>,
+        strength: f32,
+    }
+
+    #[test]
+    fn struct_of_arrays_fields_can_be_ids() {
+        let mut links = LinkComponent::<Ship>::default();
+        let link = Link {
+            target: Id::first(4),
+            strength: 0.5,
+        };
+
+        links.insert(valid(0), link.clone());
+
+        assert_eq!(Some(link), links.get(valid(0)));
+    }
+
+    mod shadowing {
+        use super::Ship;
+        use crate::test_util::valid;
+        use crate::StructOfArrays;
+
+        #[derive(Debug, Clone, PartialEq)]
+        struct Arena(u8);
+
+        #[derive(StructOfArrays)]
+        struct Venue {
+            arena: Arena,
+        }
+
+        #[test]
+        fn field_types_named_arena() {
+            let mut venues = VenueComponent::<Ship>::default();
+
+            venues.insert(valid(0), Venue { arena: Arena(2) });
+
+            assert_eq!(Some(&Arena(2)), venues.arena.get(valid(0)));
+        }
+    }
+}
@@ -0,0 +1,214 @@
+use crate::component::Component;
+use arrow_array::types::{
+    Float32Type, Float64Type, Int16Type, Int32Type, Int64Type, Int8Type, UInt16Type, UInt32Type,
+    UInt64Type, UInt8Type,
+};
+use arrow_array::{
+    Array, ArrayRef, ArrowNativeTypeOp, ArrowPrimitiveType, PrimitiveArray, RecordBatch,
+};
+use arrow_ipc::reader::FileReader;
+use arrow_ipc::writer::FileWriter;
+use arrow_schema::{ArrowError, Field, Schema};
+use std::io::{Read, Seek, Write};
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+/// Element types that can be stored as an Arrow primitive column.
+pub trait ArrowColumn: ArrowNativeTypeOp {
+    type Type: ArrowPrimitiveType<Native = Self>;
+}
+
+macro_rules! impl_arrow_column {
+    ($($ty:ty => $arrow:ty),*) => {
+        $(
+            impl ArrowColumn for $ty {
+                type Type = $arrow;
+            }
+        )*
+    };
+}
+
+impl_arrow_column!(
+    i8 => Int8Type,
+    i16 => Int16Type,
+    i32 => Int32Type,
+    i64 => Int64Type,
+    u8 => UInt8Type,
+    u16 => UInt16Type,
+    u32 => UInt32Type,
+    u64 => UInt64Type,
+    f32 => Float32Type,
+    f64 => Float64Type
+);
+
+/// Writes components of the same arena as the columns of an Arrow IPC (Feather) file.
+#[derive(Debug)]
+pub struct ArrowWriter<Arena> {
+    fields: Vec<Field>,
+    columns: Vec<ArrayRef>,
+    marker: PhantomData<*const Arena>,
+}
+
+impl<Arena> Default for ArrowWriter<Arena> {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            fields: vec![],
+            columns: vec![],
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<Arena> ArrowWriter<Arena> {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn column<T: ArrowColumn>(
+        &mut self,
+        name: &str,
+        component: &Component<Arena, T>,
+    ) -> &mut Self {
+        let array = PrimitiveArray::<T::Type>::from_iter_values(component.into_iter().copied());
+        self.fields
+            .push(Field::new(name, array.data_type().clone(), false));
+        self.columns.push(Arc::new(array));
+        self
+    }
+
+    /// Writes every column as a single record batch. Fails if the columns differ in length.
+    pub fn write<W: Write>(&self, writer: W) -> Result<(), ArrowError> {
+        let schema = Arc::new(Schema::new(self.fields.clone()));
+        let batch = RecordBatch::try_new(schema.clone(), self.columns.clone())?;
+
+        let mut writer = FileWriter::try_new(writer, &schema)?;
+        writer.write(&batch)?;
+        writer.finish()
+    }
+}
+
+/// An Arrow IPC file read into memory, from which columns are loaded into components.
+#[derive(Debug, Clone)]
+pub struct ArrowTable {
+    schema: Arc<Schema>,
+    batches: Vec<RecordBatch>,
+}
+
+impl ArrowTable {
+    pub fn read<R: Read + Seek>(reader: R) -> Result<Self, ArrowError> {
+        let reader = FileReader::try_new(reader, None)?;
+        let schema = reader.schema();
+        let batches = reader.collect::<Result<Vec<_>, _>>()?;
+        Ok(Self { schema, batches })
+    }
+
+    #[inline]
+    pub fn schema(&self) -> &Schema {
+        &self.schema
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.batches.iter().map(|b| b.num_rows()).sum()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Loads the column with this name, which must have the Arrow type of `T` and no nulls.
+    pub fn column<Arena, T: ArrowColumn>(
+        &self,
+        name: &str,
+    ) -> Result<Component<Arena, T>, ArrowError> {
+        let index = self.schema.index_of(name)?;
+        let mut values = Vec::with_capacity(self.len());
+
+        for batch in &self.batches {
+            let column = batch
+                .column(index)
+                .as_any()
+                .downcast_ref::<PrimitiveArray<T::Type>>()
+                .ok_or_else(|| {
+                    ArrowError::SchemaError(format!(
+                        "column `{}` has type {}, expected {}",
+                        name,
+                        batch.column(index).data_type(),
+                        T::Type::DATA_TYPE
+                    ))
+                })?;
+
+            if column.null_count() != 0 {
+                return Err(ArrowError::InvalidArgumentError(format!(
+                    "column `{}` contains nulls",
+                    name
+                )));
+            }
+
+            values.extend_from_slice(column.values());
+        }
+
+        Ok(values.into())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_util::values;
+    use std::io::Cursor;
+
+    fn write(writer: &ArrowWriter<()>) -> Cursor<Vec<u8>> {
+        let mut bytes = vec![];
+        writer.write(&mut bytes).unwrap();
+        Cursor::new(bytes)
+    }
+
+    #[test]
+    fn round_trip() {
+        let mass = Component::<(), f64>::from(vec![1.5, 2.5, 3.5]);
+        let count = Component::<(), u32>::from(vec![1, 2, 3]);
+        let bytes = write(
+            ArrowWriter::new()
+                .column("mass", &mass)
+                .column("count", &count),
+        );
+
+        let table = ArrowTable::read(bytes).unwrap();
+
+        assert_eq!(3, table.len());
+        assert_eq!(
+            values(&mass),
+            values(&table.column::<(), f64>("mass").unwrap())
+        );
+        assert_eq!(
+            values(&count),
+            values(&table.column::<(), u32>("count").unwrap())
+        );
+    }
+
+    #[test]
+    fn wrong_type_or_name_is_an_error() {
+        let count = Component::<(), u32>::from(vec![1, 2, 3]);
+        let table = ArrowTable::read(write(ArrowWriter::new().column("count", &count))).unwrap();
+
+        assert!(table.column::<(), f32>("count").is_err());
+        assert!(table.column::<(), u32>("mass").is_err());
+    }
+
+    #[test]
+    fn columns_must_have_equal_lengths() {
+        let a = Component::<(), u8>::from(vec![1, 2]);
+        let b = Component::<(), u8>::from(vec![1]);
+
+        let result = ArrowWriter::new()
+            .column("a", &a)
+            .column("b", &b)
+            .write(vec![]);
+
+        assert!(result.is_err());
+    }
+}
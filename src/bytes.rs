@@ -0,0 +1,72 @@
+use crate::component::Component;
+use crate::index_vec::IndexVec;
+use crate::raw_component::RawComponent;
+use crate::untyped_component::UntypedComponent;
+use bytemuck::{Pod, PodCastError};
+
+macro_rules! impl_bytes {
+    ($ty:ty, [$($generics:tt)*]) => {
+        impl<$($generics)*> $ty {
+            /// The values as raw bytes, without copying.
+            #[inline]
+            pub fn as_bytes(&self) -> &[u8] {
+                bytemuck::cast_slice(self.as_slice())
+            }
+
+            #[inline]
+            pub fn as_bytes_mut(&mut self) -> &mut [u8] {
+                bytemuck::cast_slice_mut(self.as_mut_slice())
+            }
+
+            /// Copies values out of `bytes`, which must be aligned for `T` and a multiple of its size.
+            #[inline]
+            pub fn from_bytes(bytes: &[u8]) -> Result<Self, PodCastError> {
+                bytemuck::try_cast_slice::<u8, T>(bytes).map(|values| values.to_vec().into())
+            }
+        }
+    };
+}
+
+impl_bytes!(IndexVec<T>, [T: Pod]);
+impl_bytes!(UntypedComponent<T>, [T: Pod]);
+impl_bytes!(RawComponent<Arena, T>, [Arena, T: Pod]);
+impl_bytes!(Component<Arena, T>, [Arena, T: Pod]);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let component = Component::<(), u32>::from(vec![1, 2, 3]);
+
+        let copy = Component::<(), u32>::from_bytes(component.as_bytes()).unwrap();
+
+        assert_eq!(12, component.as_bytes().len());
+        assert_eq!(component.as_slice(), copy.as_slice());
+    }
+
+    #[test]
+    fn writes_through_bytes() {
+        let mut values = IndexVec::<u16>::from(vec![0, 0]);
+
+        values.as_bytes_mut()[2..].copy_from_slice(&7u16.to_ne_bytes());
+
+        assert_eq!(&[0, 7], values.as_slice());
+    }
+
+    #[test]
+    fn from_bytes_checks_length_and_alignment() {
+        let values = [0u32; 2];
+        let bytes = bytemuck::cast_slice::<u32, u8>(&values);
+
+        assert_eq!(
+            Some(PodCastError::OutputSliceWouldHaveSlop),
+            RawComponent::<(), u32>::from_bytes(&bytes[..7]).err()
+        );
+        assert_eq!(
+            Some(PodCastError::TargetAlignmentGreaterAndInputNotAligned),
+            RawComponent::<(), u32>::from_bytes(&bytes[1..5]).err()
+        );
+    }
+}
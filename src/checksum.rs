@@ -0,0 +1,404 @@
+use crate::component::Component;
+use crate::index_vec::IndexVec;
+use crate::raw_component::RawComponent;
+use crate::untyped_component::UntypedComponent;
+use gen_id_allocator::Id;
+use std::hash::{Hash, Hasher};
+use std::ops::Range;
+
+const OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// A 64-bit FNV-1a hasher with no seed, so equal input gives equal output on every peer.
+#[derive(Debug, Copy, Clone)]
+pub struct StableHasher {
+    state: u64,
+}
+
+impl Default for StableHasher {
+    #[inline]
+    fn default() -> Self {
+        Self { state: OFFSET }
+    }
+}
+
+impl StableHasher {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[inline]
+    pub fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.state ^= *byte as u64;
+            self.state = self.state.wrapping_mul(PRIME);
+        }
+    }
+
+    #[inline]
+    pub fn finish(&self) -> u64 {
+        self.state
+    }
+}
+
+/// Lets types with a `Hash` impl feed a `StableHasher`. Integers are written as little-endian bytes,
+/// with `usize` and `isize` widened to 64 bits, so the output matches [`StableHash`].
+impl Hasher for StableHasher {
+    #[inline]
+    fn finish(&self) -> u64 {
+        self.state
+    }
+
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) {
+        StableHasher::write(self, bytes);
+    }
+
+    #[inline]
+    fn write_u16(&mut self, i: u16) {
+        self.write(&i.to_le_bytes());
+    }
+
+    #[inline]
+    fn write_u32(&mut self, i: u32) {
+        self.write(&i.to_le_bytes());
+    }
+
+    #[inline]
+    fn write_u64(&mut self, i: u64) {
+        self.write(&i.to_le_bytes());
+    }
+
+    #[inline]
+    fn write_u128(&mut self, i: u128) {
+        self.write(&i.to_le_bytes());
+    }
+
+    #[inline]
+    fn write_usize(&mut self, i: usize) {
+        self.write_u64(i as u64);
+    }
+
+    #[inline]
+    fn write_i16(&mut self, i: i16) {
+        self.write(&i.to_le_bytes());
+    }
+
+    #[inline]
+    fn write_i32(&mut self, i: i32) {
+        self.write(&i.to_le_bytes());
+    }
+
+    #[inline]
+    fn write_i64(&mut self, i: i64) {
+        self.write(&i.to_le_bytes());
+    }
+
+    #[inline]
+    fn write_i128(&mut self, i: i128) {
+        self.write(&i.to_le_bytes());
+    }
+
+    #[inline]
+    fn write_isize(&mut self, i: isize) {
+        self.write_i64(i as i64);
+    }
+}
+
+/// Hashing that does not depend on the platform, the hasher seed or the in-memory layout.
+///
+/// Integers are hashed as little-endian bytes, with `usize` and `isize` widened to 64 bits.
+/// Floats are hashed by their bits after mapping `-0.0` to `0.0` and every NaN to a single NaN.
+pub trait StableHash {
+    fn stable_hash(&self, hasher: &mut StableHasher);
+}
+
+macro_rules! impl_int {
+    ($($ty:ty),*) => {
+        $(
+            impl StableHash for $ty {
+                #[inline]
+                fn stable_hash(&self, hasher: &mut StableHasher) {
+                    hasher.write(&self.to_le_bytes());
+                }
+            }
+        )*
+    };
+}
+
+impl_int!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128);
+
+impl StableHash for usize {
+    #[inline]
+    fn stable_hash(&self, hasher: &mut StableHasher) {
+        (*self as u64).stable_hash(hasher);
+    }
+}
+
+impl StableHash for isize {
+    #[inline]
+    fn stable_hash(&self, hasher: &mut StableHasher) {
+        (*self as i64).stable_hash(hasher);
+    }
+}
+
+impl StableHash for f32 {
+    #[inline]
+    fn stable_hash(&self, hasher: &mut StableHasher) {
+        let value = if self.is_nan() {
+            f32::NAN
+        } else if *self == 0.0 {
+            0.0
+        } else {
+            *self
+        };
+        value.to_bits().stable_hash(hasher);
+    }
+}
+
+impl StableHash for f64 {
+    #[inline]
+    fn stable_hash(&self, hasher: &mut StableHasher) {
+        let value = if self.is_nan() {
+            f64::NAN
+        } else if *self == 0.0 {
+            0.0
+        } else {
+            *self
+        };
+        value.to_bits().stable_hash(hasher);
+    }
+}
+
+impl StableHash for bool {
+    #[inline]
+    fn stable_hash(&self, hasher: &mut StableHasher) {
+        (*self as u8).stable_hash(hasher);
+    }
+}
+
+impl StableHash for char {
+    #[inline]
+    fn stable_hash(&self, hasher: &mut StableHasher) {
+        (*self as u32).stable_hash(hasher);
+    }
+}
+
+impl StableHash for () {
+    #[inline]
+    fn stable_hash(&self, _: &mut StableHasher) {}
+}
+
+impl StableHash for str {
+    #[inline]
+    fn stable_hash(&self, hasher: &mut StableHasher) {
+        self.len().stable_hash(hasher);
+        hasher.write(self.as_bytes());
+    }
+}
+
+impl StableHash for String {
+    #[inline]
+    fn stable_hash(&self, hasher: &mut StableHasher) {
+        self.as_str().stable_hash(hasher);
+    }
+}
+
+impl<T: StableHash + ?Sized> StableHash for &T {
+    #[inline]
+    fn stable_hash(&self, hasher: &mut StableHasher) {
+        (**self).stable_hash(hasher);
+    }
+}
+
+/// Hashes the index and the generation of the id.
+impl<Arena> StableHash for Id<Arena> {
+    #[inline]
+    fn stable_hash(&self, hasher: &mut StableHasher) {
+        self.hash(hasher);
+    }
+}
+
+impl<T: StableHash> StableHash for Option<T> {
+    #[inline]
+    fn stable_hash(&self, hasher: &mut StableHasher) {
+        match self {
+            Some(value) => {
+                1u8.stable_hash(hasher);
+                value.stable_hash(hasher);
+            }
+            None => 0u8.stable_hash(hasher),
+        }
+    }
+}
+
+impl<T: StableHash> StableHash for [T] {
+    #[inline]
+    fn stable_hash(&self, hasher: &mut StableHasher) {
+        self.len().stable_hash(hasher);
+        self.iter().for_each(|value| value.stable_hash(hasher));
+    }
+}
+
+impl<T: StableHash> StableHash for Vec<T> {
+    #[inline]
+    fn stable_hash(&self, hasher: &mut StableHasher) {
+        self.as_slice().stable_hash(hasher);
+    }
+}
+
+macro_rules! impl_array {
+    ($($n:literal),*) => {
+        $(
+            impl<T: StableHash> StableHash for [T; $n] {
+                #[inline]
+                fn stable_hash(&self, hasher: &mut StableHasher) {
+                    self.iter().for_each(|value| value.stable_hash(hasher));
+                }
+            }
+        )*
+    };
+}
+
+impl_array!(1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16);
+
+macro_rules! impl_tuple {
+    ($($name:ident),+) => {
+        #[allow(non_snake_case)]
+        impl<$($name: StableHash),+> StableHash for ($($name,)+) {
+            #[inline]
+            fn stable_hash(&self, hasher: &mut StableHasher) {
+                let ($($name,)+) = self;
+                $($name.stable_hash(hasher);)+
+            }
+        }
+    };
+}
+
+impl_tuple!(A);
+impl_tuple!(A, B);
+impl_tuple!(A, B, C);
+impl_tuple!(A, B, C, D);
+impl_tuple!(A, B, C, D, E);
+impl_tuple!(A, B, C, D, E, F);
+
+#[inline]
+fn digest<T: StableHash>(values: &[T]) -> u64 {
+    let mut hasher = StableHasher::new();
+    values.stable_hash(&mut hasher);
+    hasher.finish()
+}
+
+#[inline]
+fn range_digest<T: StableHash>(values: &[T], range: Range<usize>) -> u64 {
+    let mut hasher = StableHasher::new();
+    (range.start as u64).stable_hash(&mut hasher);
+    values[range].stable_hash(&mut hasher);
+    hasher.finish()
+}
+
+#[inline]
+fn chunk_digests<T: StableHash>(values: &[T], chunk_len: usize) -> Vec<u64> {
+    assert!(chunk_len > 0, "chunk_len must be non-zero");
+    (0..values.len())
+        .step_by(chunk_len)
+        .map(|start| range_digest(values, start..values.len().min(start + chunk_len)))
+        .collect()
+}
+
+macro_rules! impl_checksum {
+    ($ty:ty, [$($generics:tt)*]) => {
+        impl<$($generics)*> $ty {
+            /// A digest of every value and the length, stable across platforms and runs.
+            #[inline]
+            pub fn checksum(&self) -> u64 {
+                digest(self.as_slice())
+            }
+
+            /// A digest of the values in `range`, so peers can narrow down which ids diverged.
+            #[inline]
+            pub fn checksum_range(&self, range: Range<usize>) -> u64 {
+                range_digest(self.as_slice(), range)
+            }
+
+            /// Digests of consecutive ranges of `chunk_len` values.
+            ///
+            /// # Panics
+            ///
+            /// Panics if `chunk_len` is zero.
+            #[inline]
+            pub fn checksum_chunks(&self, chunk_len: usize) -> Vec<u64> {
+                chunk_digests(self.as_slice(), chunk_len)
+            }
+        }
+    };
+}
+
+impl_checksum!(IndexVec<T>, [T: StableHash]);
+impl_checksum!(UntypedComponent<T>, [T: StableHash]);
+impl_checksum!(RawComponent<Arena, T>, [Arena, T: StableHash]);
+impl_checksum!(Component<Arena, T>, [Arena, T: StableHash]);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn fnv_reference_values() {
+        let mut hasher = StableHasher::new();
+        assert_eq!(0xcbf2_9ce4_8422_2325, hasher.finish());
+
+        hasher.write(b"a");
+        assert_eq!(0xaf63_dc4c_8601_ec8c, hasher.finish());
+    }
+
+    #[test]
+    fn floats_are_canonical() {
+        let a = Component::<(), f64>::from(vec![0.0, f64::NAN]);
+        let b = Component::<(), f64>::from(vec![-0.0, -f64::NAN]);
+
+        assert_eq!(a.checksum(), b.checksum());
+    }
+
+    #[test]
+    fn usize_matches_u64() {
+        let a = IndexVec::<usize>::from(vec![1, 2, 3]);
+        let b = IndexVec::<u64>::from(vec![1, 2, 3]);
+
+        assert_eq!(a.checksum(), b.checksum());
+    }
+
+    #[test]
+    fn chunks_locate_divergence() {
+        let a = Component::<(), u32>::from((0..10).collect::<Vec<_>>());
+        let mut values = (0..10).collect::<Vec<_>>();
+        values[7] = 100;
+        let b = Component::<(), u32>::from(values);
+
+        let diverged = a
+            .checksum_chunks(4)
+            .into_iter()
+            .zip(b.checksum_chunks(4))
+            .position(|(a, b)| a != b);
+
+        assert_ne!(a.checksum(), b.checksum());
+        assert_eq!(Some(1), diverged);
+        assert_eq!(a.checksum_range(0..7), b.checksum_range(0..7));
+    }
+
+    #[test]
+    #[should_panic(expected = "chunk_len must be non-zero")]
+    fn zero_chunk_len_panics() {
+        Component::<(), u32>::from(vec![1]).checksum_chunks(0);
+    }
+
+    #[test]
+    fn id_components() {
+        let a = Component::<(), Option<Id<()>>>::from(vec![Some(Id::first(1)), None]);
+        let b = Component::<(), Option<Id<()>>>::from(vec![Some(Id::first(2)), None]);
+
+        assert_eq!(a.checksum(), a.clone().checksum());
+        assert_ne!(a.checksum(), b.checksum());
+    }
+}
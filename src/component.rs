@@ -1,17 +1,29 @@
+use crate::id_set::IdSet;
+use crate::index_vec::IndexVec;
 use crate::raw_component::RawComponent;
-use force_derive::ForceDefault;
+use crate::storage::Storage;
 use gen_id_allocator::{Fixed, Id, IdRange, Valid, ValidId};
 use iter_context::{ContextualIterator, FromContextualIterator, Iter, IterMut};
 use ref_cast::RefCast;
+use std::iter::FromIterator;
 use std::ops::{Index, IndexMut, Neg, Not};
 
 #[repr(transparent)]
-#[derive(Debug, ForceDefault, RefCast)]
-pub struct Component<Arena, T> {
-    values: RawComponent<Arena, T>,
+#[derive(Debug, RefCast)]
+pub struct Component<Arena, T, S = IndexVec<T>> {
+    values: RawComponent<Arena, T, S>,
 }
 
-impl<Arena, T: Clone> Clone for Component<Arena, T> {
+impl<Arena, T, S: Default> Default for Component<Arena, T, S> {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            values: Default::default(),
+        }
+    }
+}
+
+impl<Arena, T, S: Clone> Clone for Component<Arena, T, S> {
     #[inline]
     fn clone(&self) -> Self {
         Self {
@@ -28,13 +40,20 @@ impl<Arena, T: Clone> Clone for Component<Arena, T> {
 impl<Arena, T> From<Vec<T>> for Component<Arena, T> {
     #[inline]
     fn from(values: Vec<T>) -> Self {
+        IndexVec::from(values).into()
+    }
+}
+
+impl<Arena, T, S: Storage<T>> From<S> for Component<Arena, T, S> {
+    #[inline]
+    fn from(values: S) -> Self {
         Component {
             values: values.into(),
         }
     }
 }
 
-impl<Arena, T> Component<Arena, T> {
+impl<Arena, T, S: Storage<T>> Component<Arena, T, S> {
     #[inline]
     pub fn insert<Id: ValidId<Arena = Arena>>(&mut self, id: Id, value: T) {
         self.insert_with(id, value, || panic!("Invalid index"));
@@ -69,19 +88,38 @@ impl<Arena, T> Component<Arena, T> {
         self.values.swap(a.id(), b.id());
     }
 
+    #[inline]
+    pub fn resize_with<F: FnMut() -> T>(&mut self, len: usize, f: F) {
+        self.values.resize_with(len, f);
+    }
+
     #[inline]
     pub fn fill_with<F: FnMut() -> T>(&mut self, f: F) {
         self.values.fill_with(f);
     }
 
+    /// The ids in `set` that have a value, together with that value.
     #[inline]
-    pub fn iter(&self) -> Iter<Arena, T> {
-        self.values.iter()
+    pub fn iter_in<'a>(
+        &'a self,
+        set: &'a IdSet<Arena>,
+    ) -> impl Iterator<Item = (Id<Arena>, &'a T)> {
+        set.iter()
+            .filter_map(move |id| self.values.get(id).map(|value| (id, value)))
     }
 
     #[inline]
-    pub fn iter_mut(&mut self) -> IterMut<Arena, T> {
-        self.values.iter_mut()
+    pub fn iter_mut_in<'a>(
+        &'a mut self,
+        set: &'a IdSet<Arena>,
+    ) -> impl Iterator<Item = (Id<Arena>, &'a mut T)> {
+        let mut values = (&mut self.values).into_iter().enumerate();
+        set.iter().filter_map(move |id| {
+            let index = id.untyped.index();
+            values
+                .find(|(i, _)| *i == index)
+                .map(|(_, value)| (id, value))
+        })
     }
 
     #[inline]
@@ -93,9 +131,41 @@ impl<Arena, T> Component<Arena, T> {
     pub fn is_empty(&self) -> bool {
         self.len() == 0
     }
+
+    #[inline]
+    pub(crate) fn raw(&self) -> &RawComponent<Arena, T, S> {
+        &self.values
+    }
+
+    #[inline]
+    pub(crate) fn raw_mut(&mut self) -> &mut RawComponent<Arena, T, S> {
+        &mut self.values
+    }
 }
 
-impl<Arena, T> Component<Arena, Option<T>> {
+impl<Arena, T> Component<Arena, T> {
+    #[inline]
+    pub(crate) fn as_slice(&self) -> &[T] {
+        self.values.as_slice()
+    }
+
+    #[inline]
+    pub(crate) fn as_mut_slice(&mut self) -> &mut [T] {
+        self.values.as_mut_slice()
+    }
+
+    #[inline]
+    pub fn iter(&self) -> Iter<Arena, T> {
+        self.values.iter()
+    }
+
+    #[inline]
+    pub fn iter_mut(&mut self) -> IterMut<Arena, T> {
+        self.values.iter_mut()
+    }
+}
+
+impl<Arena, T, S: Storage<Option<T>>> Component<Arena, Option<T>, S> {
     #[inline]
     pub fn remove<Id: ValidId<Arena = Arena>>(&mut self, id: Id) -> Option<T> {
         let value = self.values.get_mut(id.id())?;
@@ -103,7 +173,7 @@ impl<Arena, T> Component<Arena, Option<T>> {
     }
 }
 
-impl<'a, Arena, T: Copy + 'a> Component<Arena, T> {
+impl<'a, Arena, T: Copy + 'a, S: Storage<T>> Component<Arena, T, S> {
     #[inline]
     pub fn assign<Rhs: ContextualIterator<Context = Arena, Item = T>>(&mut self, rhs: Rhs) {
         self.zip(rhs).for_each(|(lhs, rhs)| *lhs = rhs);
@@ -115,7 +185,7 @@ impl<'a, Arena, T: Copy + 'a> Component<Arena, T> {
     }
 }
 
-impl<'valid, Arena, T> Index<Valid<'valid, Id<Arena>>> for Component<Arena, T> {
+impl<'valid, Arena, T, S: Storage<T>> Index<Valid<'valid, Id<Arena>>> for Component<Arena, T, S> {
     type Output = T;
 
     #[inline]
@@ -124,14 +194,16 @@ impl<'valid, Arena, T> Index<Valid<'valid, Id<Arena>>> for Component<Arena, T> {
     }
 }
 
-impl<'valid, Arena, T> IndexMut<Valid<'valid, Id<Arena>>> for Component<Arena, T> {
+impl<'valid, Arena, T, S: Storage<T>> IndexMut<Valid<'valid, Id<Arena>>>
+    for Component<Arena, T, S>
+{
     #[inline]
     fn index_mut(&mut self, index: Valid<Id<Arena>>) -> &mut Self::Output {
         self.values.index_mut(index.id())
     }
 }
 
-impl<'valid, Arena, T> Index<Valid<'valid, &Id<Arena>>> for Component<Arena, T> {
+impl<'valid, Arena, T, S: Storage<T>> Index<Valid<'valid, &Id<Arena>>> for Component<Arena, T, S> {
     type Output = T;
 
     #[inline]
@@ -140,14 +212,16 @@ impl<'valid, Arena, T> Index<Valid<'valid, &Id<Arena>>> for Component<Arena, T>
     }
 }
 
-impl<'valid, Arena, T> IndexMut<Valid<'valid, &Id<Arena>>> for Component<Arena, T> {
+impl<'valid, Arena, T, S: Storage<T>> IndexMut<Valid<'valid, &Id<Arena>>>
+    for Component<Arena, T, S>
+{
     #[inline]
     fn index_mut(&mut self, index: Valid<&Id<Arena>>) -> &mut Self::Output {
         self.values.index_mut(index.id())
     }
 }
 
-impl<Arena: Fixed, T> Index<Id<Arena>> for Component<Arena, T> {
+impl<Arena: Fixed, T, S: Storage<T>> Index<Id<Arena>> for Component<Arena, T, S> {
     type Output = T;
 
     #[inline]
@@ -156,14 +230,14 @@ impl<Arena: Fixed, T> Index<Id<Arena>> for Component<Arena, T> {
     }
 }
 
-impl<Arena: Fixed, T> IndexMut<Id<Arena>> for Component<Arena, T> {
+impl<Arena: Fixed, T, S: Storage<T>> IndexMut<Id<Arena>> for Component<Arena, T, S> {
     #[inline]
     fn index_mut(&mut self, index: Id<Arena>) -> &mut Self::Output {
         self.values.index_mut(index)
     }
 }
 
-impl<Arena: Fixed, T> Index<&Id<Arena>> for Component<Arena, T> {
+impl<Arena: Fixed, T, S: Storage<T>> Index<&Id<Arena>> for Component<Arena, T, S> {
     type Output = T;
 
     #[inline]
@@ -172,7 +246,7 @@ impl<Arena: Fixed, T> Index<&Id<Arena>> for Component<Arena, T> {
     }
 }
 
-impl<Arena: Fixed, T> IndexMut<&Id<Arena>> for Component<Arena, T> {
+impl<Arena: Fixed, T, S: Storage<T>> IndexMut<&Id<Arena>> for Component<Arena, T, S> {
     #[inline]
     fn index_mut(&mut self, index: &Id<Arena>) -> &mut Self::Output {
         self.values.index_mut(index)
@@ -243,35 +317,37 @@ impl<'valid, Arena: Fixed, T> IndexMut<Valid<'valid, &IdRange<Arena>>> for Compo
     }
 }
 
-impl<'a, Arena, T> IntoIterator for &'a Component<Arena, T> {
+impl<'a, Arena, T, S: Storage<T>> IntoIterator for &'a Component<Arena, T, S> {
     type Item = &'a T;
-    type IntoIter = std::slice::Iter<'a, T>;
+    type IntoIter = S::Iter<'a>;
 
     #[inline]
     fn into_iter(self) -> Self::IntoIter {
-        self.values.iter().into_iter()
+        self.values.into_iter()
     }
 }
 
-impl<'a, Arena, T> IntoIterator for &'a mut Component<Arena, T> {
+impl<'a, Arena, T, S: Storage<T>> IntoIterator for &'a mut Component<Arena, T, S> {
     type Item = &'a mut T;
-    type IntoIter = std::slice::IterMut<'a, T>;
+    type IntoIter = S::IterMut<'a>;
 
     #[inline]
     fn into_iter(self) -> Self::IntoIter {
-        self.values.iter_mut().into_iter()
+        (&mut self.values).into_iter()
     }
 }
 
-impl<'a, Arena, T> ContextualIterator for &'a Component<Arena, T> {
+impl<'a, Arena, T, S: Storage<T>> ContextualIterator for &'a Component<Arena, T, S> {
     type Context = Arena;
 }
 
-impl<'a, Arena, T> ContextualIterator for &'a mut Component<Arena, T> {
+impl<'a, Arena, T, S: Storage<T>> ContextualIterator for &'a mut Component<Arena, T, S> {
     type Context = Arena;
 }
 
-impl<Arena, T> FromContextualIterator<T> for Component<Arena, T> {
+impl<Arena, T, S: Storage<T> + FromIterator<T>> FromContextualIterator<T>
+    for Component<Arena, T, S>
+{
     type Context = Arena;
 
     #[inline]
@@ -279,11 +355,13 @@ impl<Arena, T> FromContextualIterator<T> for Component<Arena, T> {
     where
         Iter: ContextualIterator<Context = Self::Context, Item = T>,
     {
-        iter.into_iter().collect::<Vec<_>>().into()
+        iter.into_iter().collect::<S>().into()
     }
 }
 
-impl<'a, Arena, T: 'a + Copy> FromContextualIterator<&'a T> for Component<Arena, T> {
+impl<'a, Arena, T: 'a + Copy, S: Storage<T> + FromIterator<T>> FromContextualIterator<&'a T>
+    for Component<Arena, T, S>
+{
     type Context = Arena;
 
     #[inline]
@@ -291,14 +369,15 @@ impl<'a, Arena, T: 'a + Copy> FromContextualIterator<&'a T> for Component<Arena,
     where
         Iter: ContextualIterator<Context = Self::Context, Item = &'a T>,
     {
-        iter.into_iter().copied().collect::<Vec<_>>().into()
+        iter.into_iter().copied().collect::<S>().into()
     }
 }
 
 macro_rules! impl_op_assign {
     ($op_trait:ident, $op_fn:ident) => {
-        impl<Arena, T, Rhs> std::ops::$op_trait<Rhs> for $crate::Component<Arena, T>
+        impl<Arena, T, S, Rhs> std::ops::$op_trait<Rhs> for $crate::Component<Arena, T, S>
         where
+            S: $crate::Storage<T>,
             Rhs: iter_context::ContextualIterator<Context = Arena>,
             T: std::ops::$op_trait<Rhs::Item>,
         {
@@ -323,8 +402,10 @@ impl_op_assign!(ShrAssign, shr_assign);
 
 macro_rules! impl_op {
     ($op_trait:ident, $op_fn:ident) => {
-        impl<'a, Arena, T, Rhs, Output> std::ops::$op_trait<Rhs> for &'a $crate::Component<Arena, T>
+        impl<'a, Arena, T, S, Rhs, Output> std::ops::$op_trait<Rhs>
+            for &'a $crate::Component<Arena, T, S>
         where
+            S: $crate::Storage<T>,
             &'a T: std::ops::$op_trait<Rhs::Item, Output = Output>,
             Rhs: iter_context::ContextualIterator<Context = Arena>,
         {
@@ -353,14 +434,14 @@ impl_op!(BitAnd, bitand);
 impl_op!(BitOr, bitor);
 impl_op!(BitXor, bitxor);
 
-type MapNot<'a, Arena, T> =
-    iter_context::Map<Arena, &'a Component<Arena, T>, fn(&'a T) -> <&'a T as Not>::Output>;
+type MapNot<'a, Arena, T, S> =
+    iter_context::Map<Arena, &'a Component<Arena, T, S>, fn(&'a T) -> <&'a T as Not>::Output>;
 
-impl<'a, Arena, T> Not for &'a Component<Arena, T>
+impl<'a, Arena, T, S: Storage<T>> Not for &'a Component<Arena, T, S>
 where
     &'a T: Not,
 {
-    type Output = MapNot<'a, Arena, T>;
+    type Output = MapNot<'a, Arena, T, S>;
 
     #[inline]
     fn not(self) -> Self::Output {
@@ -368,14 +449,14 @@ where
     }
 }
 
-type MapNeg<'a, Arena, T> =
-    iter_context::Map<Arena, &'a Component<Arena, T>, fn(&'a T) -> <&'a T as Neg>::Output>;
+type MapNeg<'a, Arena, T, S> =
+    iter_context::Map<Arena, &'a Component<Arena, T, S>, fn(&'a T) -> <&'a T as Neg>::Output>;
 
-impl<'a, Arena, T> Neg for &'a Component<Arena, T>
+impl<'a, Arena, T, S: Storage<T>> Neg for &'a Component<Arena, T, S>
 where
     &'a T: Neg,
 {
-    type Output = MapNeg<'a, Arena, T>;
+    type Output = MapNeg<'a, Arena, T, S>;
 
     #[inline]
     fn neg(self) -> Self::Output {
@@ -469,6 +550,21 @@ mod test {
         );
     }
 
+    #[test]
+    fn iter_in_set() {
+        let mut comp = Component::<(), u32>::from(vec![0, 1, 2, 3]);
+        let mut set = IdSet::new();
+        set.insert(get_id(1));
+        set.insert(get_id(3));
+
+        comp.iter_mut_in(&set).for_each(|(_, value)| *value *= 10);
+
+        assert_eq!(
+            vec![(get_id(1).id(), &10), (get_id(3).id(), &30)],
+            comp.iter_in(&set).collect::<Vec<_>>()
+        );
+    }
+
     #[test]
     fn remove() {
         let id = get_id(0);
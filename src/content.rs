@@ -0,0 +1,448 @@
+use crate::component::Component;
+use gen_id_allocator::{Fixed, Id, IdRange};
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+use std::collections::{HashMap, HashSet};
+use std::fmt::{Display, Formatter};
+use std::ops::Range;
+
+const NAME: &str = "name";
+
+/// The name of a row and all of its fields.
+type Row = (String, Map<String, Value>);
+
+/// The text format of a content file.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Format {
+    Json,
+    Ron,
+}
+
+/// Returned when a content file cannot be parsed or one of its fields cannot be loaded.
+/// Rows count from zero.
+#[derive(Debug)]
+pub enum ContentError {
+    Json(serde_json::Error),
+    Ron(ron::error::SpannedError),
+    /// The file is not a list of rows.
+    NotAList,
+    /// The row is not a map, or has no string `name` field.
+    MissingName {
+        row: usize,
+    },
+    DuplicateName(String),
+    /// The field could not be converted into the component's type.
+    Field {
+        row: usize,
+        field: String,
+        message: String,
+    },
+    /// The range allocated for the rows of a fixed arena has the wrong length.
+    RangeLength {
+        rows: usize,
+        len: usize,
+    },
+    /// The field refers to a name that the target file does not define.
+    UnknownName {
+        row: usize,
+        field: String,
+        name: String,
+    },
+}
+
+impl Display for ContentError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ContentError::Json(error) => error.fmt(f),
+            ContentError::Ron(error) => error.fmt(f),
+            ContentError::NotAList => write!(f, "expected a list of rows"),
+            ContentError::MissingName { row } => write!(f, "row {} has no name", row),
+            ContentError::DuplicateName(name) => write!(f, "name `{}` is used twice", name),
+            ContentError::Field {
+                row,
+                field,
+                message,
+            } => write!(f, "row {}, field `{}`: {}", row, field, message),
+            ContentError::RangeLength { rows, len } => {
+                write!(f, "allocated {} ids for {} rows", len, rows)
+            }
+            ContentError::UnknownName { row, field, name } => {
+                write!(f, "row {}, field `{}`: unknown name `{}`", row, field, name)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ContentError {}
+
+/// Named rows read from a hand-authored content file, each of which has been given an id.
+///
+/// A file is a list of rows. Every row has a unique `name` and any number of other fields, which
+/// are loaded into components field by field. Fields missing from a row are read as null, so
+/// optional values can be left out.
+pub struct Content<Arena> {
+    ids: Vec<Id<Arena>>,
+    range: Option<IdRange<Arena>>,
+    names: HashMap<String, Id<Arena>>,
+    rows: Vec<Map<String, Value>>,
+}
+
+impl<Arena> std::fmt::Debug for Content<Arena> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Content")
+            .field("ids", &self.ids)
+            .field("range", &self.range.map(|range| range.range()))
+            .field("names", &self.names)
+            .field("rows", &self.rows)
+            .finish()
+    }
+}
+
+impl<Arena> Content<Arena> {
+    /// Parses `text` and calls `create` once per row, in order, to assign its id.
+    pub fn parse<F: FnMut() -> Id<Arena>>(
+        text: &str,
+        format: Format,
+        mut create: F,
+    ) -> Result<Self, ContentError> {
+        let rows = parse_rows(text, format)?;
+        let ids = rows.iter().map(|_| create()).collect();
+        Ok(Self::with_ids(rows, ids, None))
+    }
+
+    fn with_ids(rows: Vec<Row>, ids: Vec<Id<Arena>>, range: Option<IdRange<Arena>>) -> Self {
+        let (names, rows) = rows.into_iter().unzip::<_, _, Vec<_>, _>();
+        let names = names.into_iter().zip(ids.iter().copied()).collect();
+
+        Self {
+            ids,
+            range,
+            names,
+            rows,
+        }
+    }
+
+    /// The ids of the rows, in the order they appear in the file.
+    #[inline]
+    pub fn ids(&self) -> &[Id<Arena>] {
+        &self.ids
+    }
+
+    /// The range of ids assigned to the rows, if the content was loaded with
+    /// [`parse_range`](Self::parse_range).
+    #[inline]
+    pub fn range(&self) -> Option<IdRange<Arena>> {
+        self.range
+    }
+
+    #[inline]
+    pub fn id(&self, name: &str) -> Option<Id<Arena>> {
+        self.names.get(name).copied()
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Inserts the value of `field` for every row into `component`. Nothing is inserted if any row
+    /// fails to load.
+    pub fn insert_column<T: DeserializeOwned>(
+        &self,
+        field: &str,
+        component: &mut Component<Arena, T>,
+    ) -> Result<(), ContentError> {
+        let values = self
+            .rows
+            .iter()
+            .enumerate()
+            .map(|(row, fields)| {
+                let value = fields.get(field).cloned().unwrap_or(Value::Null);
+                serde_json::from_value(value).map_err(|error| ContentError::Field {
+                    row,
+                    field: field.to_string(),
+                    message: error.to_string(),
+                })
+            })
+            .collect::<Result<Vec<T>, _>>()?;
+
+        self.insert_all(component, values);
+        Ok(())
+    }
+
+    /// Resolves the name in `field` of every row against `targets` and inserts the ids into
+    /// `component`. Nothing is inserted if any name cannot be resolved.
+    pub fn insert_links<Other>(
+        &self,
+        field: &str,
+        targets: &Content<Other>,
+        component: &mut Component<Arena, Id<Other>>,
+    ) -> Result<(), ContentError> {
+        let links = (0..self.len())
+            .map(|row| {
+                self.link(row, field, targets)?
+                    .ok_or_else(|| ContentError::Field {
+                        row,
+                        field: field.to_string(),
+                        message: "missing name".to_string(),
+                    })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        self.insert_all(component, links);
+        Ok(())
+    }
+
+    /// Like [`insert_links`](Self::insert_links), but rows may leave the field out or set it to
+    /// null.
+    pub fn insert_optional_links<Other>(
+        &self,
+        field: &str,
+        targets: &Content<Other>,
+        component: &mut Component<Arena, Option<Id<Other>>>,
+    ) -> Result<(), ContentError> {
+        let links = (0..self.len())
+            .map(|row| self.link(row, field, targets))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        self.insert_all(component, links);
+        Ok(())
+    }
+
+    #[inline]
+    fn insert_all<T>(&self, component: &mut Component<Arena, T>, values: Vec<T>) {
+        for (id, value) in self.ids.iter().zip(values) {
+            component
+                .raw_mut()
+                .insert_with(*id, value, || panic!("Invalid index"));
+        }
+    }
+
+    fn link<Other>(
+        &self,
+        row: usize,
+        field: &str,
+        targets: &Content<Other>,
+    ) -> Result<Option<Id<Other>>, ContentError> {
+        match self.rows[row].get(field) {
+            None | Some(Value::Null) => Ok(None),
+            Some(Value::String(name)) => {
+                targets
+                    .id(name)
+                    .map(Some)
+                    .ok_or_else(|| ContentError::UnknownName {
+                        row,
+                        field: field.to_string(),
+                        name: name.clone(),
+                    })
+            }
+            Some(_) => Err(ContentError::Field {
+                row,
+                field: field.to_string(),
+                message: "expected a name".to_string(),
+            }),
+        }
+    }
+}
+
+impl<Arena: Fixed> Content<Arena> {
+    /// Parses `text` and calls `allocate` once with the number of rows, so that the rows of a
+    /// fixed arena are given one contiguous range of ids in file order.
+    pub fn parse_range<F: FnOnce(usize) -> IdRange<Arena>>(
+        text: &str,
+        format: Format,
+        allocate: F,
+    ) -> Result<Self, ContentError> {
+        let rows = parse_rows(text, format)?;
+        let range = allocate(rows.len());
+        let ids = Self::range_ids(rows.len(), range.range().range())?;
+        Ok(Self::with_ids(rows, ids, Some(range)))
+    }
+
+    /// Ids in a fixed arena are never reused, so each index in the range has a first-generation id.
+    fn range_ids(rows: usize, indices: Range<usize>) -> Result<Vec<Id<Arena>>, ContentError> {
+        if indices.len() != rows {
+            return Err(ContentError::RangeLength {
+                rows,
+                len: indices.len(),
+            });
+        }
+        Ok(indices.map(Id::first).collect())
+    }
+}
+
+/// Reads the rows of a content file, checking that every row has a unique name.
+fn parse_rows(text: &str, format: Format) -> Result<Vec<Row>, ContentError> {
+    let value = match format {
+        Format::Json => serde_json::from_str(text).map_err(ContentError::Json)?,
+        Format::Ron => ron::from_str(text).map_err(ContentError::Ron)?,
+    };
+
+    let rows = match value {
+        Value::Array(rows) => rows,
+        _ => return Err(ContentError::NotAList),
+    };
+
+    let mut names = HashSet::with_capacity(rows.len());
+    rows.into_iter()
+        .enumerate()
+        .map(|(row, value)| {
+            let fields = match value {
+                Value::Object(fields) => fields,
+                _ => return Err(ContentError::MissingName { row }),
+            };
+            let name = match fields.get(NAME) {
+                Some(Value::String(name)) => name.clone(),
+                _ => return Err(ContentError::MissingName { row }),
+            };
+            if !names.insert(name.clone()) {
+                return Err(ContentError::DuplicateName(name));
+            }
+            Ok((name, fields))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[derive(Debug)]
+    struct System;
+
+    #[derive(Debug)]
+    struct Body;
+
+    fn counter<Arena>() -> impl FnMut() -> Id<Arena> {
+        let mut next = 0;
+        move || {
+            next += 1;
+            Id::first(next - 1)
+        }
+    }
+
+    const SYSTEMS: &str = r#"[{ "name": "Sol" }, { "name": "Centauri" }]"#;
+
+    const BODIES: &str = r#"[
+        (name: "Sun", mass: 1.0, system: "Sol"),
+        (name: "Earth", mass: 0.000003, system: "Sol", parent: "Sun"),
+        (name: "Proxima", mass: 0.12, system: "Centauri"),
+    ]"#;
+
+    #[test]
+    fn loads_columns_and_links() {
+        let systems = Content::<System>::parse(SYSTEMS, Format::Json, counter()).unwrap();
+        let bodies = Content::<Body>::parse(BODIES, Format::Ron, counter()).unwrap();
+
+        let mut mass = Component::<Body, f64>::default();
+        let mut system = Component::<Body, Id<System>>::default();
+        let mut parent = Component::<Body, Option<Id<Body>>>::default();
+        bodies.insert_column("mass", &mut mass).unwrap();
+        bodies
+            .insert_links("system", &systems, &mut system)
+            .unwrap();
+        bodies
+            .insert_optional_links("parent", &bodies, &mut parent)
+            .unwrap();
+
+        assert_eq!(3, bodies.len());
+        assert_eq!(
+            vec![1.0, 0.000003, 0.12],
+            mass.into_iter().copied().collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![systems.id("Sol"), systems.id("Sol"), systems.id("Centauri")],
+            system.into_iter().map(|id| Some(*id)).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![None, bodies.id("Sun"), None],
+            parent.into_iter().copied().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn unknown_names_are_reported() {
+        let systems = Content::<System>::parse(SYSTEMS, Format::Json, counter()).unwrap();
+        let bodies = Content::<Body>::parse(
+            r#"[(name: "Vega b", system: "Vega")]"#,
+            Format::Ron,
+            counter(),
+        )
+        .unwrap();
+
+        let error = bodies
+            .insert_links("system", &systems, &mut Component::default())
+            .unwrap_err();
+
+        assert_eq!(
+            "row 0, field `system`: unknown name `Vega`",
+            error.to_string()
+        );
+    }
+
+    #[test]
+    fn rows_need_unique_names() {
+        let duplicate = r#"[{ "name": "Sol" }, { "name": "Sol" }]"#;
+        let unnamed = r#"[{ "mass": 1.0 }]"#;
+
+        assert!(matches!(
+            Content::<System>::parse(duplicate, Format::Json, counter()),
+            Err(ContentError::DuplicateName(_))
+        ));
+        assert!(matches!(
+            Content::<System>::parse(unnamed, Format::Json, counter()),
+            Err(ContentError::MissingName { row: 0 })
+        ));
+    }
+
+    #[test]
+    fn field_errors_report_row() {
+        let bodies = Content::<Body>::parse(
+            r#"[{ "name": "Sun", "mass": 1.0 }, { "name": "Moon", "mass": "light" }]"#,
+            Format::Json,
+            counter(),
+        )
+        .unwrap();
+
+        let error = bodies
+            .insert_column::<f64>("mass", &mut Component::default())
+            .unwrap_err();
+
+        assert!(matches!(error, ContentError::Field { row: 1, .. }));
+    }
+
+    #[test]
+    fn failed_columns_insert_nothing() {
+        let bodies = Content::<Body>::parse(
+            r#"[{ "name": "Sun", "mass": 1.0 }, { "name": "Moon", "mass": "light" }]"#,
+            Format::Json,
+            counter(),
+        )
+        .unwrap();
+        let mut mass = Component::<Body, f64>::default();
+
+        assert!(bodies.insert_column("mass", &mut mass).is_err());
+        assert!(mass.is_empty());
+    }
+
+    #[derive(Debug)]
+    struct Element;
+
+    impl Fixed for Element {}
+
+    #[test]
+    fn fixed_arenas_use_range_indices() {
+        let ids = Content::<Element>::range_ids(3, 4..7).unwrap();
+
+        assert_eq!(vec![Id::first(4), Id::first(5), Id::first(6)], ids);
+        assert!(matches!(
+            Content::<Element>::range_ids(3, 4..6),
+            Err(ContentError::RangeLength { rows: 3, len: 2 })
+        ));
+    }
+}
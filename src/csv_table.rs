@@ -0,0 +1,269 @@
+use crate::arena::LengthMismatch;
+use crate::component::Component;
+use std::fmt::{Display, Formatter};
+use std::io::{Read, Write};
+use std::marker::PhantomData;
+use std::str::FromStr;
+
+const ID: &str = "id";
+
+/// Returned when components cannot be written to or read from CSV.
+#[derive(Debug)]
+pub enum CsvError {
+    Csv(csv::Error),
+    /// The components written as columns do not all have the same length.
+    LengthMismatch(LengthMismatch),
+    /// The file has no column with this name.
+    MissingColumn(String),
+    /// The id in this row is not the row's index.
+    InvalidId {
+        row: usize,
+    },
+    /// A value could not be parsed. `row` counts from zero, excluding the header.
+    Parse {
+        row: usize,
+        column: String,
+        message: String,
+    },
+}
+
+impl Display for CsvError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CsvError::Csv(error) => error.fmt(f),
+            CsvError::LengthMismatch(error) => error.fmt(f),
+            CsvError::MissingColumn(name) => write!(f, "missing column `{}`", name),
+            CsvError::InvalidId { row } => write!(f, "row {} has an invalid id", row),
+            CsvError::Parse {
+                row,
+                column,
+                message,
+            } => write!(f, "row {}, column `{}`: {}", row, column, message),
+        }
+    }
+}
+
+impl std::error::Error for CsvError {}
+
+impl From<csv::Error> for CsvError {
+    #[inline]
+    fn from(error: csv::Error) -> Self {
+        CsvError::Csv(error)
+    }
+}
+
+impl From<LengthMismatch> for CsvError {
+    #[inline]
+    fn from(error: LengthMismatch) -> Self {
+        CsvError::LengthMismatch(error)
+    }
+}
+
+/// Writes components of the same arena as the columns of a CSV file, after an `id` column.
+#[derive(Debug)]
+pub struct CsvWriter<Arena> {
+    columns: Vec<(&'static str, Vec<String>)>,
+    marker: PhantomData<*const Arena>,
+}
+
+impl<Arena> Default for CsvWriter<Arena> {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            columns: vec![],
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<Arena> CsvWriter<Arena> {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn column<T: Display>(
+        &mut self,
+        name: &'static str,
+        component: &Component<Arena, T>,
+    ) -> &mut Self {
+        let values = component.into_iter().map(|v| v.to_string()).collect();
+        self.columns.push((name, values));
+        self
+    }
+
+    pub fn write<W: Write>(&self, writer: W) -> Result<(), CsvError> {
+        let expected = self.columns.first().map_or(0, |(_, values)| values.len());
+        for (column, values) in &self.columns {
+            if values.len() != expected {
+                return Err(LengthMismatch {
+                    column,
+                    len: values.len(),
+                    expected,
+                }
+                .into());
+            }
+        }
+
+        let mut writer = csv::Writer::from_writer(writer);
+        writer.write_record(std::iter::once(ID).chain(self.columns.iter().map(|(n, _)| *n)))?;
+
+        for row in 0..expected {
+            let id = row.to_string();
+            let values = self.columns.iter().map(|(_, values)| values[row].as_str());
+            writer.write_record(std::iter::once(id.as_str()).chain(values))?;
+        }
+
+        writer.flush().map_err(csv::Error::from)?;
+        Ok(())
+    }
+}
+
+/// A CSV file read into memory, from which columns are parsed into components.
+#[derive(Debug, Clone)]
+pub struct CsvTable {
+    headers: Vec<String>,
+    rows: Vec<Vec<String>>,
+}
+
+impl CsvTable {
+    /// Reads every row, checking that rows have the same number of fields and that the `id`
+    /// column, if present, matches the row index.
+    pub fn read<R: Read>(reader: R) -> Result<Self, CsvError> {
+        let mut reader = csv::Reader::from_reader(reader);
+        let headers = reader
+            .headers()?
+            .iter()
+            .map(str::to_string)
+            .collect::<Vec<_>>();
+        let id = headers.iter().position(|h| h == ID);
+
+        let mut rows = vec![];
+        for (row, record) in reader.records().enumerate() {
+            let record = record?;
+            if let Some(id) = id {
+                if record[id].trim().parse::<usize>().ok() != Some(row) {
+                    return Err(CsvError::InvalidId { row });
+                }
+            }
+            rows.push(record.iter().map(str::to_string).collect());
+        }
+
+        Ok(Self { headers, rows })
+    }
+
+    #[inline]
+    pub fn headers(&self) -> impl Iterator<Item = &str> {
+        self.headers.iter().map(String::as_str)
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn column<Arena, T>(&self, name: &str) -> Result<Component<Arena, T>, CsvError>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        let index = self
+            .headers
+            .iter()
+            .position(|h| h == name)
+            .ok_or_else(|| CsvError::MissingColumn(name.to_string()))?;
+
+        self.rows
+            .iter()
+            .enumerate()
+            .map(|(row, values)| {
+                values[index]
+                    .trim()
+                    .parse()
+                    .map_err(|error: T::Err| CsvError::Parse {
+                        row,
+                        column: name.to_string(),
+                        message: error.to_string(),
+                    })
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map(Component::from)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_util::values;
+
+    #[test]
+    fn round_trip() {
+        let mass = Component::<(), f32>::from(vec![1.5, 2.0]);
+        let count = Component::<(), u32>::from(vec![3, 4]);
+        let mut bytes = vec![];
+        CsvWriter::new()
+            .column("mass", &mass)
+            .column("count", &count)
+            .write(&mut bytes)
+            .unwrap();
+
+        assert_eq!(
+            "id,mass,count\n0,1.5,3\n1,2,4\n",
+            String::from_utf8_lossy(&bytes)
+        );
+
+        let table = CsvTable::read(bytes.as_slice()).unwrap();
+        let mass = table.column::<(), f32>("mass").unwrap();
+        let count = table.column::<(), u32>("count").unwrap();
+
+        assert_eq!(vec![1.5, 2.0], values(&mass));
+        assert_eq!(vec![3, 4], values(&count));
+    }
+
+    #[test]
+    fn write_checks_lengths() {
+        let a = Component::<(), u32>::from(vec![1, 2]);
+        let b = Component::<(), u32>::from(vec![1]);
+
+        let error = CsvWriter::new()
+            .column("a", &a)
+            .column("b", &b)
+            .write(vec![])
+            .unwrap_err();
+
+        assert!(matches!(
+            error,
+            CsvError::LengthMismatch(LengthMismatch { column: "b", .. })
+        ));
+    }
+
+    #[test]
+    fn parse_errors_report_row_and_column() {
+        let table = CsvTable::read("id,mass\n0,1\n1,heavy\n".as_bytes()).unwrap();
+
+        let error = table.column::<(), f32>("mass").unwrap_err();
+
+        assert!(matches!(error, CsvError::Parse { row: 1, ref column, .. } if column == "mass"));
+        assert!(matches!(
+            table.column::<(), f32>("size").unwrap_err(),
+            CsvError::MissingColumn(_)
+        ));
+    }
+
+    #[test]
+    fn read_checks_ids_and_row_lengths() {
+        assert!(matches!(
+            CsvTable::read("id,mass\n1,1\n".as_bytes()).unwrap_err(),
+            CsvError::InvalidId { row: 0 }
+        ));
+        assert!(matches!(
+            CsvTable::read("id,mass\n0,1\n1\n".as_bytes()).unwrap_err(),
+            CsvError::Csv(_)
+        ));
+    }
+}
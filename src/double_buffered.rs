@@ -0,0 +1,143 @@
+use crate::component::Component;
+use gen_id_allocator::ValidId;
+
+/// A pair of components where systems read last tick's values from `current`
+/// and write this tick's values into `next`.
+#[derive(Debug)]
+pub struct DoubleBuffered<Arena, T> {
+    current: Component<Arena, T>,
+    next: Component<Arena, T>,
+}
+
+impl<Arena, T> Default for DoubleBuffered<Arena, T> {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            current: Default::default(),
+            next: Default::default(),
+        }
+    }
+}
+
+impl<Arena, T: Clone> Clone for DoubleBuffered<Arena, T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            current: self.current.clone(),
+            next: self.next.clone(),
+        }
+    }
+
+    #[inline]
+    fn clone_from(&mut self, source: &Self) {
+        self.current.clone_from(&source.current);
+        self.next.clone_from(&source.next);
+    }
+}
+
+impl<Arena, T: Clone> From<Vec<T>> for DoubleBuffered<Arena, T> {
+    #[inline]
+    fn from(values: Vec<T>) -> Self {
+        Self {
+            next: values.clone().into(),
+            current: values.into(),
+        }
+    }
+}
+
+impl<Arena, T> DoubleBuffered<Arena, T> {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[inline]
+    pub fn current(&self) -> &Component<Arena, T> {
+        &self.current
+    }
+
+    #[inline]
+    pub fn next(&self) -> &Component<Arena, T> {
+        &self.next
+    }
+
+    #[inline]
+    pub fn next_mut(&mut self) -> &mut Component<Arena, T> {
+        &mut self.next
+    }
+
+    /// Borrows `current` for reading and `next` for writing at the same time.
+    #[inline]
+    pub fn split(&mut self) -> (&Component<Arena, T>, &mut Component<Arena, T>) {
+        (&self.current, &mut self.next)
+    }
+
+    /// Makes `next` the new `current`. The old `current` becomes `next` and keeps its stale values.
+    #[inline]
+    pub fn flip(&mut self) {
+        std::mem::swap(&mut self.current, &mut self.next);
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.current.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.current.is_empty()
+    }
+}
+
+impl<Arena, T: Clone> DoubleBuffered<Arena, T> {
+    /// Makes `next` the new `current`, then copies it into `next` so that ids that are not written
+    /// during the following tick keep their values.
+    #[inline]
+    pub fn flip_and_clone(&mut self) {
+        self.flip();
+        self.next.clone_from(&self.current);
+    }
+
+    /// Inserts the value into both buffers.
+    #[inline]
+    pub fn insert<Id: ValidId<Arena = Arena> + Copy>(&mut self, id: Id, value: T) {
+        self.next.insert(id, value.clone());
+        self.current.insert(id, value);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_util::values;
+
+    #[test]
+    fn reads_are_unaffected_until_flip() {
+        let mut buffer = DoubleBuffered::<(), u32>::from(vec![1, 2]);
+
+        let (current, next) = buffer.split();
+        next.assign(current + current);
+
+        assert_eq!(vec![1, 2], values(buffer.current()));
+
+        buffer.flip();
+
+        assert_eq!(vec![2, 4], values(buffer.current()));
+        assert_eq!(vec![1, 2], values(buffer.next()));
+    }
+
+    #[test]
+    fn flip_and_clone_keeps_unwritten_values() {
+        let mut buffer = DoubleBuffered::<(), u32>::from(vec![1, 2]);
+        buffer
+            .next_mut()
+            .iter_mut()
+            .into_iter()
+            .for_each(|v| *v += 10);
+
+        buffer.flip_and_clone();
+
+        assert_eq!(vec![11, 12], values(buffer.current()));
+        assert_eq!(vec![11, 12], values(buffer.next()));
+    }
+}
@@ -0,0 +1,150 @@
+use crate::component::Component;
+use crate::raw_component::RawComponent;
+use gen_id_allocator::Id;
+use std::any::Any;
+
+/// Type-erased operations shared by every component over a single arena.
+pub trait DynComponent<Arena>: Any {
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Truncates or extends the component, filling new slots with default values.
+    fn resize(&mut self, len: usize);
+
+    /// Sets the value for `id` back to its default, if the component has a value for it.
+    fn reset(&mut self, id: Id<Arena>);
+
+    fn swap(&mut self, a: Id<Arena>, b: Id<Arena>);
+
+    /// Clones `source` into `self`, reusing the existing allocation.
+    ///
+    /// Panics if `source` is not the same component type as `self`.
+    fn clone_from_dyn(&mut self, source: &dyn DynComponent<Arena>);
+
+    fn clone_box(&self) -> Box<dyn DynComponent<Arena>>;
+
+    fn type_name(&self) -> &'static str;
+
+    fn as_any(&self) -> &dyn Any;
+
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+
+    fn into_any(self: Box<Self>) -> Box<dyn Any>;
+}
+
+impl<Arena: 'static, T: Clone + Default + 'static> DynComponent<Arena> for RawComponent<Arena, T> {
+    #[inline]
+    fn len(&self) -> usize {
+        self.len()
+    }
+
+    #[inline]
+    fn resize(&mut self, len: usize) {
+        self.resize_with(len, Default::default);
+    }
+
+    #[inline]
+    fn reset(&mut self, id: Id<Arena>) {
+        if let Some(value) = self.get_mut(id) {
+            *value = T::default();
+        }
+    }
+
+    #[inline]
+    fn swap(&mut self, a: Id<Arena>, b: Id<Arena>) {
+        self.swap(a, b);
+    }
+
+    #[inline]
+    fn clone_from_dyn(&mut self, source: &dyn DynComponent<Arena>) {
+        let source = source
+            .as_any()
+            .downcast_ref::<Self>()
+            .expect("Component type mismatch");
+        self.clone_from(source);
+    }
+
+    #[inline]
+    fn clone_box(&self) -> Box<dyn DynComponent<Arena>> {
+        Box::new(self.clone())
+    }
+
+    #[inline]
+    fn type_name(&self) -> &'static str {
+        std::any::type_name::<Self>()
+    }
+
+    #[inline]
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    #[inline]
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    #[inline]
+    fn into_any(self: Box<Self>) -> Box<dyn Any> {
+        self
+    }
+}
+
+impl<Arena: 'static, T: Clone + Default + 'static> DynComponent<Arena> for Component<Arena, T> {
+    #[inline]
+    fn len(&self) -> usize {
+        self.len()
+    }
+
+    #[inline]
+    fn resize(&mut self, len: usize) {
+        self.resize_with(len, Default::default);
+    }
+
+    #[inline]
+    fn reset(&mut self, id: Id<Arena>) {
+        DynComponent::reset(self.raw_mut(), id);
+    }
+
+    #[inline]
+    fn swap(&mut self, a: Id<Arena>, b: Id<Arena>) {
+        self.raw_mut().swap(a, b);
+    }
+
+    #[inline]
+    fn clone_from_dyn(&mut self, source: &dyn DynComponent<Arena>) {
+        let source = source
+            .as_any()
+            .downcast_ref::<Self>()
+            .expect("Component type mismatch");
+        self.clone_from(source);
+    }
+
+    #[inline]
+    fn clone_box(&self) -> Box<dyn DynComponent<Arena>> {
+        Box::new(self.clone())
+    }
+
+    #[inline]
+    fn type_name(&self) -> &'static str {
+        std::any::type_name::<Self>()
+    }
+
+    #[inline]
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    #[inline]
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    #[inline]
+    fn into_any(self: Box<Self>) -> Box<dyn Any> {
+        self
+    }
+}
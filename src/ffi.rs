@@ -0,0 +1,243 @@
+//! `extern "C"` functions over `UntypedComponent` for plain-old-data element types.
+//!
+//! Every element type gets the same set of functions, prefixed with `gid_component_{type}_`. The
+//! declarations are in `include/gen_id_component.h`, which is generated by [`c_header`]. The
+//! functions for each type are in a module named after it, such as [`component_f32`].
+//!
+//! The crate builds as an `rlib` by default. To link it from C, build a shared or static library:
+//!
+//! ```text
+//! cargo rustc --release --lib --features ffi --crate-type cdylib
+//! cargo rustc --release --lib --features ffi --crate-type staticlib
+//! ```
+
+macro_rules! ffi {
+    ($($module:ident: $ty:ident: $c_ty:literal),*) => {
+        $(
+            ffi_functions!($module, $ty, $c_ty);
+        )*
+
+        /// The C declarations of every function in this module.
+        pub fn c_header() -> String {
+            let mut header = String::from(HEADER_START);
+            $(
+                header.push_str(&declarations(stringify!($ty), $c_ty));
+            )*
+            header.push_str(HEADER_END);
+            header
+        }
+    };
+}
+
+macro_rules! ffi_functions {
+    ($module:ident, $ty:ident, $c_ty:literal) => {
+        pub mod $module {
+            use crate::untyped_component::UntypedComponent;
+
+            /// Creates an empty component, which must be released with `free`.
+            #[export_name = concat!("gid_component_", stringify!($ty), "_new")]
+            pub extern "C" fn new() -> *mut UntypedComponent<$ty> {
+                Box::into_raw(Box::default())
+            }
+
+            /// # Safety
+            ///
+            /// `component` must be null or have come from `new`, and must not be used afterwards.
+            #[export_name = concat!("gid_component_", stringify!($ty), "_free")]
+            pub unsafe extern "C" fn free(component: *mut UntypedComponent<$ty>) {
+                if !component.is_null() {
+                    drop(Box::from_raw(component));
+                }
+            }
+
+            /// # Safety
+            ///
+            /// `component` must be a live component from `new`.
+            #[export_name = concat!("gid_component_", stringify!($ty), "_len")]
+            pub unsafe extern "C" fn len(component: *const UntypedComponent<$ty>) -> usize {
+                (*component).len()
+            }
+
+            /// A pointer to the first of `len` values, valid until the component is resized.
+            ///
+            /// # Safety
+            ///
+            /// `component` must be a live component from `new`.
+            #[export_name = concat!("gid_component_", stringify!($ty), "_data")]
+            pub unsafe extern "C" fn data(component: *mut UntypedComponent<$ty>) -> *mut $ty {
+                (*component).as_mut_slice().as_mut_ptr()
+            }
+
+            /// Sets the value at `index`, filling any gap before it with zeroes. Returns `false`,
+            /// leaving the component unchanged, if it cannot grow to hold `index`.
+            ///
+            /// # Safety
+            ///
+            /// `component` must be a live component from `new`.
+            #[export_name = concat!("gid_component_", stringify!($ty), "_insert")]
+            pub unsafe extern "C" fn insert(
+                component: *mut UntypedComponent<$ty>,
+                index: usize,
+                value: $ty,
+            ) -> bool {
+                let component = &mut *component;
+                if index >= component.len() {
+                    match index.checked_add(1) {
+                        Some(len) if component.try_resize(len) => {}
+                        _ => return false,
+                    }
+                }
+                component.as_mut_slice()[index] = value;
+                true
+            }
+
+            /// Replaces the contents of the component with `len` values read from `source`.
+            ///
+            /// # Safety
+            ///
+            /// `component` must be a live component from `new`, and `source` must point to `len`
+            /// readable values.
+            #[export_name = concat!("gid_component_", stringify!($ty), "_copy_from")]
+            pub unsafe extern "C" fn copy_from(
+                component: *mut UntypedComponent<$ty>,
+                source: *const $ty,
+                len: usize,
+            ) {
+                let component = &mut *component;
+                component.resize_with(len, Default::default);
+                if len != 0 {
+                    let source = std::slice::from_raw_parts(source, len);
+                    component.as_mut_slice().copy_from_slice(source);
+                }
+            }
+
+            /// Copies up to `capacity` values into `target` and returns the number copied.
+            ///
+            /// # Safety
+            ///
+            /// `component` must be a live component from `new`, and `target` must point to
+            /// `capacity` writable values.
+            #[export_name = concat!("gid_component_", stringify!($ty), "_copy_to")]
+            pub unsafe extern "C" fn copy_to(
+                component: *const UntypedComponent<$ty>,
+                target: *mut $ty,
+                capacity: usize,
+            ) -> usize {
+                let values = (*component).as_slice();
+                let len = values.len().min(capacity);
+                if len != 0 {
+                    std::slice::from_raw_parts_mut(target, len).copy_from_slice(&values[..len]);
+                }
+                len
+            }
+        }
+    };
+}
+
+const HEADER_START: &str = "\
+/* Generated by gen_id_component::ffi::c_header. Do not edit. */
+
+#ifndef GEN_ID_COMPONENT_H
+#define GEN_ID_COMPONENT_H
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern \"C\" {
+#endif
+";
+
+const HEADER_END: &str = "
+#ifdef __cplusplus
+}
+#endif
+
+#endif
+";
+
+fn declarations(name: &str, c_ty: &str) -> String {
+    let component = format!("gid_component_{}", name);
+    format!(
+        "
+typedef struct {component} {component};
+
+{component} *{component}_new(void);
+void {component}_free({component} *component);
+size_t {component}_len(const {component} *component);
+{c_ty} *{component}_data({component} *component);
+bool {component}_insert({component} *component, size_t index, {c_ty} value);
+void {component}_copy_from({component} *component, const {c_ty} *source, size_t len);
+size_t {component}_copy_to(const {component} *component, {c_ty} *target, size_t capacity);
+",
+        component = component,
+        c_ty = c_ty
+    )
+}
+
+ffi!(
+    component_u8: u8: "uint8_t",
+    component_u16: u16: "uint16_t",
+    component_u32: u32: "uint32_t",
+    component_u64: u64: "uint64_t",
+    component_i8: i8: "int8_t",
+    component_i16: i16: "int16_t",
+    component_i32: i32: "int32_t",
+    component_i64: i64: "int64_t",
+    component_f32: f32: "float",
+    component_f64: f64: "double"
+);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn header_is_up_to_date() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/include/gen_id_component.h");
+        if std::env::var_os("UPDATE_HEADER").is_some() {
+            std::fs::write(path, c_header()).unwrap();
+        }
+
+        let header = std::fs::read_to_string(path).unwrap();
+        assert_eq!(
+            c_header(),
+            header,
+            "rerun with UPDATE_HEADER=1 to regenerate"
+        );
+    }
+
+    #[test]
+    fn insert_and_copy() {
+        unsafe {
+            let component = component_f32::new();
+
+            assert!(component_f32::insert(component, 2, 5.0));
+            assert_eq!(3, component_f32::len(component));
+            assert_eq!(5.0, *component_f32::data(component).add(2));
+
+            component_f32::copy_from(component, [1.0, 2.0].as_ptr(), 2);
+            let mut target = [0.0; 4];
+            let copied = component_f32::copy_to(component, target.as_mut_ptr(), target.len());
+
+            assert_eq!(2, copied);
+            assert_eq!([1.0, 2.0, 0.0, 0.0], target);
+
+            component_f32::free(component);
+        }
+    }
+
+    #[test]
+    fn insert_reports_failure() {
+        unsafe {
+            let component = component_u64::new();
+
+            assert!(!component_u64::insert(component, usize::MAX, 1));
+            assert!(!component_u64::insert(component, usize::MAX / 8, 1));
+            assert_eq!(0, component_u64::len(component));
+
+            component_u64::free(component);
+        }
+    }
+}
@@ -0,0 +1,169 @@
+use crate::component::Component;
+use crate::index_vec::IndexVec;
+use gen_id_allocator::Id;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum Visit {
+    Unvisited,
+    InProgress,
+    Done,
+}
+
+impl Default for Visit {
+    #[inline]
+    fn default() -> Self {
+        Visit::Unvisited
+    }
+}
+
+/// The ids that form a loop in a parent component, in child-to-ancestor order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cycle<Node> {
+    pub ids: Vec<Id<Node>>,
+}
+
+/// An ordering of nodes in which every parent comes before its children.
+#[derive(Debug, Clone)]
+pub struct Hierarchy<Node> {
+    order: Vec<Id<Node>>,
+}
+
+impl<Node> Hierarchy<Node> {
+    /// Orders the given ids so that each parent precedes its children.
+    ///
+    /// Parents that are not in `ids` are still included ahead of their children.
+    pub fn new<I>(ids: I, parents: &Component<Node, Option<Id<Node>>>) -> Result<Self, Cycle<Node>>
+    where
+        I: IntoIterator<Item = Id<Node>>,
+    {
+        let mut visits = IndexVec::<Visit>::new();
+        let mut order = Vec::new();
+        let mut path = Vec::new();
+
+        for id in ids {
+            let mut current = Some(id);
+
+            while let Some(node) = current {
+                let index = node.untyped.index();
+
+                match visits.get(index).copied().unwrap_or_default() {
+                    Visit::Done => break,
+                    Visit::InProgress => {
+                        let start = path
+                            .iter()
+                            .position(|p: &Id<Node>| p.untyped.index() == index);
+                        let ids = path.split_off(start.unwrap_or(0));
+                        return Err(Cycle { ids });
+                    }
+                    Visit::Unvisited => {
+                        visits.insert(index, Visit::InProgress);
+                        path.push(node);
+                        current = parents.raw().get(node).copied().flatten();
+                    }
+                }
+            }
+
+            for node in path.drain(..).rev() {
+                visits.insert(node.untyped.index(), Visit::Done);
+                order.push(node);
+            }
+        }
+
+        Ok(Self { order })
+    }
+
+    #[inline]
+    pub fn order(&self) -> &[Id<Node>] {
+        &self.order
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.order.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.order.is_empty()
+    }
+
+    /// Computes `world[child] = combine(world[parent], local[child])` in parent-first order.
+    ///
+    /// Roots are passed `None` in place of a parent value.
+    pub fn propagate<W, L, F>(
+        &self,
+        parents: &Component<Node, Option<Id<Node>>>,
+        world: &mut Component<Node, W>,
+        local: &Component<Node, L>,
+        combine: F,
+    ) where
+        F: Fn(Option<&W>, &L) -> W,
+    {
+        for &id in &self.order {
+            let parent = parents.raw().get(id).copied().flatten();
+            let value = combine(parent.map(|p| &world.raw()[p]), &local.raw()[id]);
+            world.raw_mut()[id] = value;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn id(i: usize) -> Id<()> {
+        Id::first(i)
+    }
+
+    fn parents(values: Vec<Option<usize>>) -> Component<(), Option<Id<()>>> {
+        values
+            .into_iter()
+            .map(|p| p.map(id))
+            .collect::<Vec<_>>()
+            .into()
+    }
+
+    #[test]
+    fn parents_come_before_children() {
+        let parents = parents(vec![Some(2), None, Some(1)]);
+
+        let hierarchy = Hierarchy::new((0..3).map(id), &parents).unwrap();
+
+        assert_eq!(&[id(1), id(2), id(0)], hierarchy.order());
+    }
+
+    #[test]
+    fn cycle_returns_offending_ids() {
+        let parents = parents(vec![None, Some(2), Some(3), Some(1)]);
+
+        let cycle = Hierarchy::new((0..4).map(id), &parents).unwrap_err();
+
+        assert_eq!(vec![id(1), id(2), id(3)], cycle.ids);
+    }
+
+    #[test]
+    fn self_parent_is_a_cycle() {
+        let parents = parents(vec![Some(0)]);
+
+        let cycle = Hierarchy::new(Some(id(0)), &parents).unwrap_err();
+
+        assert_eq!(vec![id(0)], cycle.ids);
+    }
+
+    #[test]
+    fn propagate_offsets() {
+        let parents = parents(vec![Some(2), None, Some(1)]);
+        let local = Component::<(), u32>::from(vec![1, 10, 100]);
+        let mut world = Component::<(), u32>::from(vec![0, 0, 0]);
+
+        let hierarchy = Hierarchy::new((0..3).map(id), &parents).unwrap();
+        hierarchy.propagate(&parents, &mut world, &local, |parent, local| {
+            parent.copied().unwrap_or_default() + local
+        });
+
+        assert_eq!(
+            vec![111, 10, 110],
+            world.into_iter().copied().collect::<Vec<_>>()
+        );
+    }
+}
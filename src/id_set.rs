@@ -0,0 +1,332 @@
+use crate::index_vec::IndexVec;
+use gen_id_allocator::{Id, ValidId};
+use iter_context::ContextualIterator;
+use std::ops::{BitAnd, BitOr, Sub};
+
+const BITS: usize = 64;
+
+/// A set of ids stored as a bitset over their indices.
+///
+/// The full id for each member is kept alongside the bits so that iteration yields ids that can be
+/// used to index other components.
+#[derive(Debug)]
+pub struct IdSet<Arena> {
+    words: Vec<u64>,
+    ids: IndexVec<Option<Id<Arena>>>,
+    len: usize,
+}
+
+impl<Arena> Default for IdSet<Arena> {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            words: Vec::new(),
+            ids: IndexVec::new(),
+            len: 0,
+        }
+    }
+}
+
+impl<Arena> Clone for IdSet<Arena> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            words: self.words.clone(),
+            ids: self.ids.clone(),
+            len: self.len,
+        }
+    }
+
+    #[inline]
+    fn clone_from(&mut self, source: &Self) {
+        self.words.clone_from(&source.words);
+        self.ids.clone_from(&source.ids);
+        self.len = source.len;
+    }
+}
+
+impl<Arena> IdSet<Arena> {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Builds a set from the ids whose mask value is `true`.
+    pub fn from_mask<Ids, Mask>(ids: Ids, mask: Mask) -> Self
+    where
+        Ids: ContextualIterator<Context = Arena>,
+        Ids::Item: ValidId<Arena = Arena>,
+        Mask: ContextualIterator<Context = Arena, Item = bool>,
+    {
+        let mut set = Self::new();
+        ids.zip(mask)
+            .into_iter()
+            .filter(|(_, included)| *included)
+            .for_each(|(id, _)| {
+                set.insert(id);
+            });
+        set
+    }
+
+    /// Adds an id to the set, returning `true` if it was not already present.
+    #[inline]
+    pub fn insert<Id: ValidId<Arena = Arena>>(&mut self, id: Id) -> bool {
+        let id = id.id();
+        let index = id.untyped.index();
+        let (word, bit) = (index / BITS, 1 << (index % BITS));
+
+        if word >= self.words.len() {
+            self.words.resize(word + 1, 0);
+        }
+
+        let added = !self.contains_id(id);
+        if self.words[word] & bit == 0 {
+            self.len += 1;
+        }
+
+        self.words[word] |= bit;
+        self.ids.insert(index, Some(id));
+        added
+    }
+
+    /// Removes an id from the set, returning `true` if it was present.
+    #[inline]
+    pub fn remove<Id: ValidId<Arena = Arena>>(&mut self, id: Id) -> bool {
+        let id = id.id();
+        if !self.contains_id(id) {
+            return false;
+        }
+
+        let index = id.untyped.index();
+        self.clear_bit(index);
+        self.ids[index] = None;
+        self.len -= 1;
+        true
+    }
+
+    #[inline]
+    pub fn contains<Id: ValidId<Arena = Arena>>(&self, id: Id) -> bool {
+        self.contains_id(id.id())
+    }
+
+    #[inline]
+    pub(crate) fn contains_id(&self, id: Id<Arena>) -> bool {
+        self.contains_index(id.untyped.index())
+            && self.ids.get(id.untyped.index()) == Some(&Some(id))
+    }
+
+    #[inline]
+    pub(crate) fn contains_index(&self, index: usize) -> bool {
+        self.words
+            .get(index / BITS)
+            .is_some_and(|word| word & (1 << (index % BITS)) != 0)
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    #[inline]
+    pub fn clear(&mut self) {
+        self.words.clear();
+        self.ids.fill_with(|| None);
+        self.len = 0;
+    }
+
+    /// The ids in the set, in index order.
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = Id<Arena>> + '_ {
+        self.indices().filter_map(move |index| self.ids[index])
+    }
+
+    #[inline]
+    pub(crate) fn indices(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words.iter().enumerate().flat_map(|(word, &bits)| {
+            let mut bits = bits;
+            std::iter::from_fn(move || {
+                if bits == 0 {
+                    return None;
+                }
+                let bit = bits.trailing_zeros() as usize;
+                bits &= bits - 1;
+                Some(word * BITS + bit)
+            })
+        })
+    }
+
+    pub fn union_with(&mut self, other: &Self) {
+        if self.words.len() < other.words.len() {
+            self.words.resize(other.words.len(), 0);
+        }
+        for index in other.indices() {
+            if !self.contains_index(index) {
+                self.ids.insert(index, other.ids[index]);
+            }
+        }
+        self.words
+            .iter_mut()
+            .zip(&other.words)
+            .for_each(|(lhs, rhs)| *lhs |= rhs);
+        self.recount();
+    }
+
+    /// Keeps the ids that are also in `other`, comparing generations as well as indices.
+    pub fn intersect_with(&mut self, other: &Self) {
+        let stale = self
+            .indices()
+            .filter(|&index| self.ids[index] != other.id_at(index))
+            .collect::<Vec<_>>();
+        stale.into_iter().for_each(|index| self.clear_bit(index));
+        self.clear_unset_ids();
+    }
+
+    /// Removes the ids that are also in `other`, comparing generations as well as indices.
+    pub fn difference_with(&mut self, other: &Self) {
+        for index in other.indices() {
+            if self.contains_index(index) && self.ids[index] == other.ids[index] {
+                self.clear_bit(index);
+            }
+        }
+        self.clear_unset_ids();
+    }
+
+    #[inline]
+    pub fn union(&self, other: &Self) -> Self {
+        let mut set = self.clone();
+        set.union_with(other);
+        set
+    }
+
+    #[inline]
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut set = self.clone();
+        set.intersect_with(other);
+        set
+    }
+
+    #[inline]
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut set = self.clone();
+        set.difference_with(other);
+        set
+    }
+
+    #[inline]
+    fn clear_unset_ids(&mut self) {
+        for index in 0..self.ids.len() {
+            if !self.contains_index(index) {
+                self.ids[index] = None;
+            }
+        }
+        self.recount();
+    }
+
+    #[inline]
+    fn id_at(&self, index: usize) -> Option<Id<Arena>> {
+        if self.contains_index(index) {
+            self.ids[index]
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    fn clear_bit(&mut self, index: usize) {
+        self.words[index / BITS] &= !(1 << (index % BITS));
+    }
+
+    #[inline]
+    fn recount(&mut self) {
+        self.len = self.words.iter().map(|w| w.count_ones() as usize).sum();
+    }
+}
+
+impl<Arena> BitOr for &IdSet<Arena> {
+    type Output = IdSet<Arena>;
+
+    #[inline]
+    fn bitor(self, rhs: Self) -> Self::Output {
+        self.union(rhs)
+    }
+}
+
+impl<Arena> BitAnd for &IdSet<Arena> {
+    type Output = IdSet<Arena>;
+
+    #[inline]
+    fn bitand(self, rhs: Self) -> Self::Output {
+        self.intersection(rhs)
+    }
+}
+
+impl<Arena> Sub for &IdSet<Arena> {
+    type Output = IdSet<Arena>;
+
+    #[inline]
+    fn sub(self, rhs: Self) -> Self::Output {
+        self.difference(rhs)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_util::valid;
+    use crate::Component;
+    use gen_id_allocator::Valid;
+
+    fn set(indices: &[usize]) -> IdSet<()> {
+        let mut set = IdSet::new();
+        indices.iter().for_each(|i| {
+            set.insert(valid(*i));
+        });
+        set
+    }
+
+    fn indices(set: &IdSet<()>) -> Vec<usize> {
+        set.iter().map(|id| id.untyped.index()).collect()
+    }
+
+    #[test]
+    fn insert_remove_contains() {
+        let mut set = IdSet::<()>::new();
+
+        assert!(set.insert(valid(70)));
+        assert!(!set.insert(valid(70)));
+        assert!(set.contains(valid(70)));
+        assert!(!set.contains(valid(3)));
+        assert_eq!(1, set.len());
+
+        assert!(set.remove(valid(70)));
+        assert!(!set.remove(valid(70)));
+        assert!(set.is_empty());
+    }
+
+    #[test]
+    fn set_algebra() {
+        let a = set(&[1, 2, 65]);
+        let b = set(&[2, 3, 65, 130]);
+
+        assert_eq!(vec![1, 2, 3, 65, 130], indices(&(&a | &b)));
+        assert_eq!(vec![2, 65], indices(&(&a & &b)));
+        assert_eq!(vec![1], indices(&(&a - &b)));
+        assert_eq!(vec![3, 130], indices(&(&b - &a)));
+        assert_eq!(2, (&a & &b).len());
+    }
+
+    #[test]
+    fn from_mask() {
+        let ids = Component::<(), Valid<Id<()>>>::from((0..4).map(valid).collect::<Vec<_>>());
+        let mask = Component::<(), bool>::from(vec![true, false, false, true]);
+
+        let set = IdSet::from_mask(ids.iter().map(|id| *id), mask.iter().map(|b| *b));
+
+        assert_eq!(vec![0, 3], indices(&set));
+    }
+}
@@ -1,5 +1,7 @@
+use crate::storage::Storage;
 use force_derive::ForceDefault;
 use ref_cast::RefCast;
+use std::iter::FromIterator;
 use std::ops::Range;
 use std::ops::{Index, IndexMut};
 
@@ -68,11 +70,35 @@ impl<T> IndexVec<T> {
         self.values.swap(a, b);
     }
 
+    #[inline]
+    pub fn resize_with<F: FnMut() -> T>(&mut self, len: usize, f: F) {
+        self.values.resize_with(len, f);
+    }
+
     #[inline]
     pub fn fill_with<F: FnMut() -> T>(&mut self, f: F) {
         self.values.fill_with(f);
     }
 
+    #[cfg(feature = "ffi")]
+    #[inline]
+    pub(crate) fn try_reserve(
+        &mut self,
+        additional: usize,
+    ) -> Result<(), std::collections::TryReserveError> {
+        self.values.try_reserve(additional)
+    }
+
+    #[inline]
+    pub fn as_slice(&self) -> &[T] {
+        &self.values
+    }
+
+    #[inline]
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        &mut self.values
+    }
+
     #[inline]
     pub fn iter(&self) -> std::slice::Iter<T> {
         self.values.iter()
@@ -129,6 +155,15 @@ impl<T> From<Vec<T>> for IndexVec<T> {
     }
 }
 
+impl<T> FromIterator<T> for IndexVec<T> {
+    #[inline]
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Self {
+            values: iter.into_iter().collect(),
+        }
+    }
+}
+
 impl<T> From<IndexVec<T>> for Vec<T> {
     #[inline]
     fn from(values: IndexVec<T>) -> Self {
@@ -136,6 +171,63 @@ impl<T> From<IndexVec<T>> for Vec<T> {
     }
 }
 
+impl<T> Storage<T> for IndexVec<T> {
+    type Iter<'a>
+        = std::slice::Iter<'a, T>
+    where
+        T: 'a;
+
+    type IterMut<'a>
+        = std::slice::IterMut<'a, T>
+    where
+        T: 'a;
+
+    #[inline]
+    fn insert_with<F: Fn() -> T>(&mut self, index: usize, value: T, fill: F) {
+        IndexVec::insert_with(self, index, value, fill);
+    }
+
+    #[inline]
+    fn get(&self, index: usize) -> Option<&T> {
+        self.values.get(index)
+    }
+
+    #[inline]
+    fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        self.values.get_mut(index)
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    #[inline]
+    fn swap(&mut self, a: usize, b: usize) {
+        self.values.swap(a, b);
+    }
+
+    #[inline]
+    fn resize_with<F: FnMut() -> T>(&mut self, len: usize, f: F) {
+        self.values.resize_with(len, f);
+    }
+
+    #[inline]
+    fn fill_with<F: FnMut() -> T>(&mut self, f: F) {
+        self.values.fill_with(f);
+    }
+
+    #[inline]
+    fn iter(&self) -> Self::Iter<'_> {
+        self.values.iter()
+    }
+
+    #[inline]
+    fn iter_mut(&mut self) -> Self::IterMut<'_> {
+        self.values.iter_mut()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
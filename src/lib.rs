@@ -1,9 +1,87 @@
+#[cfg(all(test, feature = "derive"))]
+extern crate self as gen_id_component;
+
+pub use arena::LengthMismatch;
+pub use checksum::{StableHash, StableHasher};
 pub use component::Component;
+pub use double_buffered::DoubleBuffered;
+pub use dyn_component::DynComponent;
+pub use hierarchy::{Cycle, Hierarchy};
+pub use id_set::IdSet;
 pub use index_vec::IndexVec;
+pub use paged::{
+    PagedComponent, PagedIter, PagedIterMut, PagedRawComponent, PagedUntypedComponent, PagedValues,
+    PagedValuesMut, PagedVec, PAGE_LEN,
+};
+pub use pair_component::{Column, PairComponent, SymmetricPairComponent, SymmetricRow};
+pub use patch::Patch;
+pub use query::{query, Fetch, IndexedQuery, Join, Query, Required};
 pub use raw_component::RawComponent;
+pub use registry::Registry;
+pub use relation::Relation;
+pub use replication::{Codec, DecodeError, Decoder, Encoder, LittleEndian};
+pub use rollback::Rollback;
+pub use snapshot::{Migrations, Snapshot, SnapshotEntry, SnapshotError, SnapshotWriter};
+pub use storage::Storage;
+pub use tag::Tag;
 pub use untyped_component::UntypedComponent;
 
+#[cfg(feature = "arrow")]
+pub use arrow::{ArrowColumn, ArrowTable, ArrowWriter};
+#[cfg(feature = "content")]
+pub use content::{Content, ContentError, Format};
+#[cfg(feature = "csv")]
+pub use csv_table::{CsvError, CsvTable, CsvWriter};
+#[cfg(feature = "derive")]
+pub use gen_id_component_derive::{Arena, StructOfArrays};
+#[cfg(feature = "mmap")]
+pub use mmap_component::MmapComponent;
+#[cfg(all(unix, feature = "shm"))]
+pub use shared_component::{SharedComponent, SharedReader, SharedVec};
+#[cfg(feature = "sqlite")]
+pub use sqlite::{SqlColumn, SqliteError, SqliteStore};
+
+mod arena;
+#[cfg(feature = "arrow")]
+mod arrow;
+#[cfg(feature = "bytemuck")]
+mod bytes;
+mod checksum;
 mod component;
+#[cfg(feature = "content")]
+mod content;
+#[cfg(feature = "csv")]
+mod csv_table;
+mod double_buffered;
+mod dyn_component;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+mod hierarchy;
+mod id_set;
 mod index_vec;
+#[cfg(feature = "mmap")]
+mod mmap_component;
+mod paged;
+mod pair_component;
+mod patch;
+mod query;
 mod raw_component;
+mod registry;
+mod relation;
+mod replication;
+mod rollback;
+#[cfg(all(unix, feature = "shm"))]
+mod shared_component;
+mod snapshot;
+#[cfg(feature = "sqlite")]
+mod sqlite;
+mod storage;
+mod tag;
+#[cfg(test)]
+mod test_util;
 mod untyped_component;
+
+#[doc(hidden)]
+pub mod __private {
+    pub use gen_id_allocator::ValidId;
+}
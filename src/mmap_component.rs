@@ -0,0 +1,367 @@
+use crate::component::Component;
+use bytemuck::Pod;
+use gen_id_allocator::{Fixed, Id, IdRange, Valid, ValidId};
+use iter_context::{ContextualIterator, Iter, IterMut};
+use memmap2::{MmapMut, MmapOptions};
+use std::convert::TryFrom;
+use std::fs::OpenOptions;
+use std::io::{Error, ErrorKind, Result};
+use std::marker::PhantomData;
+use std::ops::{Index, IndexMut};
+use std::path::Path;
+
+const MAGIC: &[u8; 8] = b"GIDCMMAP";
+const HEADER_LEN: usize = 32;
+
+/// A component of plain-old-data values stored in a memory-mapped file.
+///
+/// The file starts with a 32-byte header recording the element size, alignment and length, which
+/// are verified when the file is opened. Writes reach the file when it is flushed or dropped, unless
+/// it was opened with [`open_read_only`](MmapComponent::open_read_only).
+#[derive(Debug)]
+pub struct MmapComponent<Arena, T> {
+    map: MmapMut,
+    len: usize,
+    marker: PhantomData<*const (Arena, T)>,
+}
+
+impl<Arena, T: Pod> MmapComponent<Arena, T> {
+    /// Creates or truncates the file at `path` and fills it with the values of `component`.
+    pub fn create<P: AsRef<Path>>(path: P, component: &Component<Arena, T>) -> Result<Self> {
+        Self::check_layout()?;
+
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(path)?;
+        let values = bytemuck::cast_slice::<T, u8>(component.as_slice());
+        file.set_len((HEADER_LEN + values.len()) as u64)?;
+
+        let mut map = unsafe { MmapMut::map_mut(&file)? };
+        map[..HEADER_LEN].copy_from_slice(&Self::header(component.len()));
+        map[HEADER_LEN..].copy_from_slice(values);
+
+        Ok(Self {
+            map,
+            len: component.len(),
+            marker: PhantomData,
+        })
+    }
+
+    /// Maps an existing file, checking that its header matches `T`.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::check_layout()?;
+
+        let file = OpenOptions::new().read(true).write(true).open(path)?;
+        let map = unsafe { MmapMut::map_mut(&file)? };
+
+        Self::from_map(map)
+    }
+
+    /// Maps an existing file that may not be writable, checking that its header matches `T`.
+    ///
+    /// The mapping is copy-on-write: values can still be changed in memory, but the changes are
+    /// never written back to the file.
+    pub fn open_read_only<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::check_layout()?;
+
+        let file = OpenOptions::new().read(true).open(path)?;
+        let map = unsafe { MmapOptions::new().map_copy(&file)? };
+
+        Self::from_map(map)
+    }
+
+    fn from_map(map: MmapMut) -> Result<Self> {
+        if map.len() < HEADER_LEN || &map[..MAGIC.len()] != MAGIC {
+            return Err(invalid_data("not a memory-mapped component"));
+        }
+
+        let header = &map[..HEADER_LEN];
+        let size = read_u64(&header[8..16]);
+        let align = read_u64(&header[16..24]);
+        let len = usize::try_from(read_u64(&header[24..32]))
+            .map_err(|_| invalid_data("file length does not match"))?;
+
+        if size != std::mem::size_of::<T>() as u64 || align != std::mem::align_of::<T>() as u64 {
+            return Err(invalid_data("element layout does not match"));
+        }
+
+        let expected = len
+            .checked_mul(std::mem::size_of::<T>())
+            .and_then(|bytes| bytes.checked_add(HEADER_LEN));
+        if expected != Some(map.len()) {
+            return Err(invalid_data("file length does not match"));
+        }
+
+        Ok(Self {
+            map,
+            len,
+            marker: PhantomData,
+        })
+    }
+
+    #[inline]
+    pub fn flush(&self) -> Result<()> {
+        self.map.flush()
+    }
+
+    #[inline]
+    pub fn get<Id: ValidId<Arena = Arena>>(&self, id: Id) -> Option<&T> {
+        self.as_slice().get(id.id().untyped.index())
+    }
+
+    #[inline]
+    pub fn get_mut<Id: ValidId<Arena = Arena>>(&mut self, id: Id) -> Option<&mut T> {
+        self.as_mut_slice().get_mut(id.id().untyped.index())
+    }
+
+    #[inline]
+    pub fn as_slice(&self) -> &[T] {
+        bytemuck::cast_slice(&self.map[HEADER_LEN..])
+    }
+
+    #[inline]
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        bytemuck::cast_slice_mut(&mut self.map[HEADER_LEN..])
+    }
+
+    #[inline]
+    pub fn iter(&self) -> Iter<Arena, T> {
+        Iter::new(self.as_slice().iter())
+    }
+
+    #[inline]
+    pub fn iter_mut(&mut self) -> IterMut<Arena, T> {
+        IterMut::new(self.as_mut_slice().iter_mut())
+    }
+
+    /// Copies the values into an owned component.
+    #[inline]
+    pub fn to_component(&self) -> Component<Arena, T> {
+        self.as_slice().to_vec().into()
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn check_layout() -> Result<()> {
+        if std::mem::align_of::<T>() > HEADER_LEN || std::mem::size_of::<T>() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "unsupported element layout",
+            ));
+        }
+        Ok(())
+    }
+
+    fn header(len: usize) -> [u8; HEADER_LEN] {
+        let mut header = [0; HEADER_LEN];
+        header[..8].copy_from_slice(MAGIC);
+        header[8..16].copy_from_slice(&(std::mem::size_of::<T>() as u64).to_le_bytes());
+        header[16..24].copy_from_slice(&(std::mem::align_of::<T>() as u64).to_le_bytes());
+        header[24..32].copy_from_slice(&(len as u64).to_le_bytes());
+        header
+    }
+}
+
+#[inline]
+fn read_u64(bytes: &[u8]) -> u64 {
+    let mut buffer = [0; 8];
+    buffer.copy_from_slice(bytes);
+    u64::from_le_bytes(buffer)
+}
+
+#[inline]
+fn invalid_data(message: &str) -> Error {
+    Error::new(ErrorKind::InvalidData, message)
+}
+
+impl<'valid, Arena, T: Pod> Index<Valid<'valid, Id<Arena>>> for MmapComponent<Arena, T> {
+    type Output = T;
+
+    #[inline]
+    fn index(&self, index: Valid<Id<Arena>>) -> &Self::Output {
+        &self.as_slice()[index.value.untyped.index()]
+    }
+}
+
+impl<'valid, Arena, T: Pod> IndexMut<Valid<'valid, Id<Arena>>> for MmapComponent<Arena, T> {
+    #[inline]
+    fn index_mut(&mut self, index: Valid<Id<Arena>>) -> &mut Self::Output {
+        &mut self.as_mut_slice()[index.value.untyped.index()]
+    }
+}
+
+impl<'valid, Arena, T: Pod> Index<Valid<'valid, &Id<Arena>>> for MmapComponent<Arena, T> {
+    type Output = T;
+
+    #[inline]
+    fn index(&self, index: Valid<&Id<Arena>>) -> &Self::Output {
+        &self.as_slice()[index.value.untyped.index()]
+    }
+}
+
+impl<'valid, Arena, T: Pod> IndexMut<Valid<'valid, &Id<Arena>>> for MmapComponent<Arena, T> {
+    #[inline]
+    fn index_mut(&mut self, index: Valid<&Id<Arena>>) -> &mut Self::Output {
+        &mut self.as_mut_slice()[index.value.untyped.index()]
+    }
+}
+
+impl<Arena: Fixed, T: Pod> Index<Id<Arena>> for MmapComponent<Arena, T> {
+    type Output = T;
+
+    #[inline]
+    fn index(&self, index: Id<Arena>) -> &Self::Output {
+        &self.as_slice()[index.untyped.index()]
+    }
+}
+
+impl<Arena: Fixed, T: Pod> IndexMut<Id<Arena>> for MmapComponent<Arena, T> {
+    #[inline]
+    fn index_mut(&mut self, index: Id<Arena>) -> &mut Self::Output {
+        &mut self.as_mut_slice()[index.untyped.index()]
+    }
+}
+
+impl<Arena: Fixed, T: Pod> Index<&Id<Arena>> for MmapComponent<Arena, T> {
+    type Output = T;
+
+    #[inline]
+    fn index(&self, index: &Id<Arena>) -> &Self::Output {
+        &self.as_slice()[index.untyped.index()]
+    }
+}
+
+impl<Arena: Fixed, T: Pod> IndexMut<&Id<Arena>> for MmapComponent<Arena, T> {
+    #[inline]
+    fn index_mut(&mut self, index: &Id<Arena>) -> &mut Self::Output {
+        &mut self.as_mut_slice()[index.untyped.index()]
+    }
+}
+
+impl<Arena: Fixed, T: Pod> Index<IdRange<Arena>> for MmapComponent<Arena, T> {
+    type Output = [T];
+
+    #[inline]
+    fn index(&self, index: IdRange<Arena>) -> &Self::Output {
+        &self.as_slice()[index.range().range()]
+    }
+}
+
+impl<Arena: Fixed, T: Pod> IndexMut<IdRange<Arena>> for MmapComponent<Arena, T> {
+    #[inline]
+    fn index_mut(&mut self, index: IdRange<Arena>) -> &mut Self::Output {
+        &mut self.as_mut_slice()[index.range().range()]
+    }
+}
+
+impl<'a, Arena, T: Pod> IntoIterator for &'a MmapComponent<Arena, T> {
+    type Item = &'a T;
+    type IntoIter = std::slice::Iter<'a, T>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.as_slice().iter()
+    }
+}
+
+impl<'a, Arena, T: Pod> ContextualIterator for &'a MmapComponent<Arena, T> {
+    type Context = Arena;
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_util::TempFile;
+    use std::fs::File;
+
+    #[derive(Debug)]
+    struct Star;
+
+    impl Fixed for Star {}
+
+    #[test]
+    fn create_write_and_reopen() {
+        let path = TempFile::new("reopen.mmap");
+        let values = Component::<Star, f32>::from(vec![1.0, 2.0, 3.0]);
+
+        let mut mapped = MmapComponent::create(&path, &values).unwrap();
+        mapped[Id::first(1)] = 5.0;
+        mapped.flush().unwrap();
+        drop(mapped);
+
+        let mapped = MmapComponent::<Star, f32>::open(&path).unwrap();
+        assert_eq!(&[1.0, 5.0, 3.0], mapped.as_slice());
+        assert_eq!(3.0, mapped[Id::first(2)]);
+    }
+
+    #[test]
+    fn open_rejects_other_element_type() {
+        let path = TempFile::new("layout.mmap");
+        let values = Component::<Star, u32>::from(vec![1, 2]);
+        MmapComponent::create(&path, &values).unwrap();
+
+        let error = MmapComponent::<Star, u64>::open(&path).unwrap_err();
+
+        assert_eq!(ErrorKind::InvalidData, error.kind());
+    }
+
+    #[test]
+    fn open_rejects_truncated_file() {
+        let path = TempFile::new("truncated.mmap");
+        let values = Component::<Star, u32>::from(vec![1, 2]);
+        MmapComponent::create(&path, &values).unwrap();
+        File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_len(HEADER_LEN as u64 + 4)
+            .unwrap();
+
+        let error = MmapComponent::<Star, u32>::open(&path).unwrap_err();
+
+        assert_eq!(ErrorKind::InvalidData, error.kind());
+    }
+
+    #[test]
+    fn open_rejects_overflowing_length() {
+        let path = TempFile::new("overflow.mmap");
+        let values = Component::<Star, u32>::from(vec![1, 2]);
+        MmapComponent::create(&path, &values).unwrap();
+        let mut mapped = MmapComponent::<Star, u32>::open(&path).unwrap();
+        mapped.map[24..32].copy_from_slice(&(u64::MAX / 2).to_le_bytes());
+        drop(mapped);
+
+        let error = MmapComponent::<Star, u32>::open(&path).unwrap_err();
+
+        assert_eq!(ErrorKind::InvalidData, error.kind());
+    }
+
+    #[test]
+    fn read_only_changes_stay_in_memory() {
+        let path = TempFile::new("read_only.mmap");
+        let values = Component::<Star, u32>::from(vec![1, 2]);
+        MmapComponent::create(&path, &values).unwrap();
+        let mut permissions = std::fs::metadata(&path).unwrap().permissions();
+        permissions.set_readonly(true);
+        std::fs::set_permissions(&path, permissions).unwrap();
+
+        let mut mapped = MmapComponent::<Star, u32>::open_read_only(&path).unwrap();
+        mapped[Id::first(0)] = 3;
+        assert_eq!(&[3, 2], mapped.as_slice());
+        drop(mapped);
+
+        let mapped = MmapComponent::<Star, u32>::open_read_only(&path).unwrap();
+        assert_eq!(&[1, 2], mapped.as_slice());
+    }
+}
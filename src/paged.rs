@@ -0,0 +1,508 @@
+use crate::component::Component;
+use crate::raw_component::RawComponent;
+use crate::storage::Storage;
+use crate::untyped_component::UntypedComponent;
+use iter_context::ContextualIterator;
+use std::iter::FromIterator;
+use std::marker::PhantomData;
+use std::ops::{Index, IndexMut};
+
+/// The number of values in each page.
+pub const PAGE_LEN: usize = 1024;
+
+/// Iterates over the values of every page in order.
+pub type PagedValues<'a, T> = std::iter::Flatten<std::slice::Iter<'a, Vec<T>>>;
+
+pub type PagedValuesMut<'a, T> = std::iter::Flatten<std::slice::IterMut<'a, Vec<T>>>;
+
+/// A [`Storage`] that keeps its values in fixed-size pages, so growing it never moves them.
+///
+/// Each page is allocated at full size and never grows, so growing the vec neither moves existing
+/// values nor copies them, and references to values stay at the same address.
+#[derive(Debug)]
+pub struct PagedVec<T> {
+    pages: Vec<Vec<T>>,
+    len: usize,
+}
+
+impl<T> Default for PagedVec<T> {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            pages: vec![],
+            len: 0,
+        }
+    }
+}
+
+impl<T: Clone> Clone for PagedVec<T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        let mut clone = Self::default();
+        clone.clone_from(self);
+        clone
+    }
+
+    fn clone_from(&mut self, source: &Self) {
+        self.truncate(source.len);
+        for (page, source) in self.pages.iter_mut().zip(&source.pages) {
+            page.truncate(source.len());
+            let (existing, new) = source.split_at(page.len());
+            page.clone_from_slice(existing);
+            page.extend_from_slice(new);
+        }
+        // The existing pages now match the source, so the last of them may have grown
+        self.len = source.len.min(self.pages.len() * PAGE_LEN);
+        source.pages[self.pages.len()..]
+            .iter()
+            .flatten()
+            .for_each(|value| self.push(value.clone()));
+    }
+}
+
+impl<T> PagedVec<T> {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[inline]
+    pub fn insert_with<F: Fn() -> T>(&mut self, index: usize, value: T, fill: F) {
+        if let Some(current) = self.get_mut(index) {
+            *current = value;
+        } else {
+            while self.len < index {
+                self.push(fill());
+            }
+            self.push(value);
+        }
+    }
+
+    #[inline]
+    pub fn get(&self, index: usize) -> Option<&T> {
+        self.pages.get(index / PAGE_LEN)?.get(index % PAGE_LEN)
+    }
+
+    #[inline]
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        self.pages
+            .get_mut(index / PAGE_LEN)?
+            .get_mut(index % PAGE_LEN)
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn swap(&mut self, a: usize, b: usize) {
+        let (page_a, page_b) = (a / PAGE_LEN, b / PAGE_LEN);
+        if page_a == page_b {
+            self.pages[page_a].swap(a % PAGE_LEN, b % PAGE_LEN);
+        } else {
+            let (low, high) = (page_a.min(page_b), page_a.max(page_b));
+            let (left, right) = self.pages.split_at_mut(high);
+            let (a, b) = if page_a < page_b { (a, b) } else { (b, a) };
+            std::mem::swap(&mut left[low][a % PAGE_LEN], &mut right[0][b % PAGE_LEN]);
+        }
+    }
+
+    #[inline]
+    pub fn resize_with<F: FnMut() -> T>(&mut self, len: usize, mut f: F) {
+        self.truncate(len);
+        while self.len < len {
+            self.push(f());
+        }
+    }
+
+    #[inline]
+    pub fn fill_with<F: FnMut() -> T>(&mut self, mut f: F) {
+        self.iter_mut().for_each(|value| *value = f());
+    }
+
+    /// The pages in order. Every page but the last holds `PAGE_LEN` values.
+    #[inline]
+    pub fn pages(&self) -> impl Iterator<Item = &[T]> {
+        self.pages.iter().map(Vec::as_slice)
+    }
+
+    #[inline]
+    pub fn pages_mut(&mut self) -> impl Iterator<Item = &mut [T]> {
+        self.pages.iter_mut().map(Vec::as_mut_slice)
+    }
+
+    #[inline]
+    pub fn iter(&self) -> PagedValues<T> {
+        self.pages.iter().flatten()
+    }
+
+    #[inline]
+    pub fn iter_mut(&mut self) -> PagedValuesMut<T> {
+        self.pages.iter_mut().flatten()
+    }
+
+    #[inline]
+    fn push(&mut self, value: T) {
+        if self.len.is_multiple_of(PAGE_LEN) {
+            self.pages.push(Vec::with_capacity(PAGE_LEN));
+        }
+        self.pages[self.len / PAGE_LEN].push(value);
+        self.len += 1;
+    }
+
+    #[inline]
+    fn truncate(&mut self, len: usize) {
+        if len < self.len {
+            let pages = len.div_ceil(PAGE_LEN);
+            self.pages.truncate(pages);
+            if let Some(page) = self.pages.last_mut() {
+                page.truncate(len - (pages - 1) * PAGE_LEN);
+            }
+            self.len = len;
+        }
+    }
+}
+
+impl<T: Default> PagedVec<T> {
+    #[inline]
+    pub fn insert(&mut self, index: usize, value: T) {
+        self.insert_with(index, value, Default::default)
+    }
+}
+
+impl<T> Index<usize> for PagedVec<T> {
+    type Output = T;
+
+    #[inline]
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.pages[index / PAGE_LEN][index % PAGE_LEN]
+    }
+}
+
+impl<T> IndexMut<usize> for PagedVec<T> {
+    #[inline]
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        &mut self.pages[index / PAGE_LEN][index % PAGE_LEN]
+    }
+}
+
+impl<T> From<Vec<T>> for PagedVec<T> {
+    #[inline]
+    fn from(values: Vec<T>) -> Self {
+        values.into_iter().collect()
+    }
+}
+
+impl<T> FromIterator<T> for PagedVec<T> {
+    #[inline]
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut values = Self::default();
+        iter.into_iter().for_each(|value| values.push(value));
+        values
+    }
+}
+
+impl<'a, T> IntoIterator for &'a PagedVec<T> {
+    type Item = &'a T;
+    type IntoIter = PagedValues<'a, T>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut PagedVec<T> {
+    type Item = &'a mut T;
+    type IntoIter = PagedValuesMut<'a, T>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl<T> Storage<T> for PagedVec<T> {
+    type Iter<'a>
+        = PagedValues<'a, T>
+    where
+        T: 'a;
+
+    type IterMut<'a>
+        = PagedValuesMut<'a, T>
+    where
+        T: 'a;
+
+    #[inline]
+    fn insert_with<F: Fn() -> T>(&mut self, index: usize, value: T, fill: F) {
+        PagedVec::insert_with(self, index, value, fill);
+    }
+
+    #[inline]
+    fn get(&self, index: usize) -> Option<&T> {
+        PagedVec::get(self, index)
+    }
+
+    #[inline]
+    fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        PagedVec::get_mut(self, index)
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    #[inline]
+    fn swap(&mut self, a: usize, b: usize) {
+        PagedVec::swap(self, a, b);
+    }
+
+    #[inline]
+    fn resize_with<F: FnMut() -> T>(&mut self, len: usize, f: F) {
+        PagedVec::resize_with(self, len, f);
+    }
+
+    #[inline]
+    fn fill_with<F: FnMut() -> T>(&mut self, f: F) {
+        PagedVec::fill_with(self, f);
+    }
+
+    #[inline]
+    fn iter(&self) -> Self::Iter<'_> {
+        PagedVec::iter(self)
+    }
+
+    #[inline]
+    fn iter_mut(&mut self) -> Self::IterMut<'_> {
+        PagedVec::iter_mut(self)
+    }
+}
+
+/// An `UntypedComponent` backed by a [`PagedVec`].
+pub type PagedUntypedComponent<T> = UntypedComponent<T, PagedVec<T>>;
+
+/// A `RawComponent` backed by a [`PagedVec`].
+pub type PagedRawComponent<Arena, T> = RawComponent<Arena, T, PagedVec<T>>;
+
+/// A `Component` backed by a [`PagedVec`].
+pub type PagedComponent<Arena, T> = Component<Arena, T, PagedVec<T>>;
+
+impl<T> PagedUntypedComponent<T> {
+    #[inline]
+    pub fn pages(&self) -> impl Iterator<Item = &[T]> {
+        self.storage().pages()
+    }
+
+    #[inline]
+    pub fn pages_mut(&mut self) -> impl Iterator<Item = &mut [T]> {
+        self.storage_mut().pages_mut()
+    }
+}
+
+impl<Arena, T> PagedRawComponent<Arena, T> {
+    #[inline]
+    pub fn pages(&self) -> impl Iterator<Item = &[T]> {
+        self.storage().pages()
+    }
+
+    #[inline]
+    pub fn pages_mut(&mut self) -> impl Iterator<Item = &mut [T]> {
+        self.storage_mut().pages_mut()
+    }
+
+    #[inline]
+    pub fn iter(&self) -> PagedIter<Arena, T> {
+        PagedIter::new(self.storage().iter())
+    }
+
+    #[inline]
+    pub fn iter_mut(&mut self) -> PagedIterMut<Arena, T> {
+        PagedIterMut::new(self.storage_mut().iter_mut())
+    }
+}
+
+impl<Arena, T> PagedComponent<Arena, T> {
+    #[inline]
+    pub fn pages(&self) -> impl Iterator<Item = &[T]> {
+        self.raw().pages()
+    }
+
+    #[inline]
+    pub fn pages_mut(&mut self) -> impl Iterator<Item = &mut [T]> {
+        self.raw_mut().pages_mut()
+    }
+
+    #[inline]
+    pub fn iter(&self) -> PagedIter<Arena, T> {
+        self.raw().iter()
+    }
+
+    #[inline]
+    pub fn iter_mut(&mut self) -> PagedIterMut<Arena, T> {
+        self.raw_mut().iter_mut()
+    }
+}
+
+/// The values of a paged component in order, tagged with the component's arena.
+pub struct PagedIter<'a, Arena, T> {
+    iter: PagedValues<'a, T>,
+    marker: PhantomData<*const Arena>,
+}
+
+impl<'a, Arena, T> PagedIter<'a, Arena, T> {
+    #[inline]
+    pub fn new(iter: PagedValues<'a, T>) -> Self {
+        Self {
+            iter,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'a, Arena, T> IntoIterator for PagedIter<'a, Arena, T> {
+    type Item = &'a T;
+    type IntoIter = PagedValues<'a, T>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter
+    }
+}
+
+impl<'a, Arena, T> ContextualIterator for PagedIter<'a, Arena, T> {
+    type Context = Arena;
+}
+
+pub struct PagedIterMut<'a, Arena, T> {
+    iter: PagedValuesMut<'a, T>,
+    marker: PhantomData<*const Arena>,
+}
+
+impl<'a, Arena, T> PagedIterMut<'a, Arena, T> {
+    #[inline]
+    pub fn new(iter: PagedValuesMut<'a, T>) -> Self {
+        Self {
+            iter,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'a, Arena, T> IntoIterator for PagedIterMut<'a, Arena, T> {
+    type Item = &'a mut T;
+    type IntoIter = PagedValuesMut<'a, T>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter
+    }
+}
+
+impl<'a, Arena, T> ContextualIterator for PagedIterMut<'a, Arena, T> {
+    type Context = Arena;
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::rollback::Rollback;
+
+    #[test]
+    fn growth_keeps_addresses() {
+        let mut values = PagedVec::<u32>::new();
+        values.insert(0, 7);
+        let first = &values[0] as *const u32;
+
+        values.insert(10 * PAGE_LEN, 1);
+
+        assert_eq!(first, &values[0] as *const u32);
+        assert_eq!(10 * PAGE_LEN + 1, values.len());
+        assert_eq!(11, values.pages().count());
+    }
+
+    #[test]
+    fn swap_across_pages() {
+        let mut values = PagedVec::from((0..2 * PAGE_LEN).collect::<Vec<_>>());
+
+        values.swap(1, PAGE_LEN + 1);
+
+        assert_eq!(PAGE_LEN + 1, values[1]);
+        assert_eq!(1, values[PAGE_LEN + 1]);
+    }
+
+    #[test]
+    fn resize_and_clone_from() {
+        let mut values = PagedVec::from(vec![1u32; PAGE_LEN + 5]);
+        let mut clone = PagedVec::from(vec![2u32; 3 * PAGE_LEN]);
+
+        values.resize_with(PAGE_LEN - 1, || 0);
+        clone.clone_from(&values);
+
+        assert_eq!(PAGE_LEN - 1, clone.len());
+        assert_eq!(1, clone.pages().count());
+        assert!(clone.iter().all(|v| *v == 1));
+    }
+
+    #[test]
+    fn clone_from_grows_shorter_target() {
+        let source = PagedVec::from((0..2 * PAGE_LEN + 3).collect::<Vec<_>>());
+        let mut clone = PagedVec::from(vec![0; 5]);
+        let first = &clone[0] as *const usize;
+
+        clone.clone_from(&source);
+
+        assert_eq!(source.len(), clone.len());
+        assert_eq!(3, clone.pages().count());
+        assert!(clone.pages().all(|page| page.len() <= PAGE_LEN));
+        assert_eq!(Some(&(PAGE_LEN - 1)), clone.get(PAGE_LEN - 1));
+        assert_eq!(Some(&PAGE_LEN), clone.get(PAGE_LEN));
+        assert_eq!(Some(&(2 * PAGE_LEN + 2)), clone.get(2 * PAGE_LEN + 2));
+        assert_eq!(None, clone.get(2 * PAGE_LEN + 3));
+        assert_eq!(first, &clone[0] as *const usize);
+        assert!(clone.iter().eq(source.iter()));
+    }
+
+    #[test]
+    fn rollback_of_growing_component() {
+        let mut state = PagedComponent::<(), usize>::from(PagedVec::from(vec![0; 3]));
+        let mut rollback = Rollback::with_capacity(1);
+        rollback.save(0, &state);
+
+        state = PagedComponent::from(PagedVec::from((0..2 * PAGE_LEN + 1).collect::<Vec<_>>()));
+        rollback.save(1, &state);
+        let mut restored = PagedComponent::from(PagedVec::from(vec![0; 3]));
+
+        assert!(rollback.restore(1, &mut restored));
+        assert_eq!(state.len(), restored.len());
+        assert!(restored.pages().eq(state.pages()));
+    }
+
+    #[test]
+    fn contextual_iteration() {
+        let a = PagedRawComponent::<(), u32>::from(PagedVec::from(vec![1; PAGE_LEN + 1]));
+        let mut b = PagedRawComponent::<(), u32>::default();
+        b.resize_with(PAGE_LEN + 1, || 0);
+
+        b.assign(a.iter().zip(&a).map(|(a, b)| a + b));
+
+        assert!(b.iter().into_iter().all(|v| *v == 2));
+    }
+
+    #[test]
+    fn component_operators() {
+        let ones = PagedComponent::<(), u32>::from(PagedVec::from(vec![1; PAGE_LEN + 1]));
+        let mut target = PagedComponent::<(), u32>::from(PagedVec::from(vec![0; PAGE_LEN + 1]));
+
+        target += &ones + &ones;
+        target -= &ones;
+
+        assert_eq!(2, target.pages().count());
+        assert!(target.iter().into_iter().all(|v| *v == 1));
+    }
+}
@@ -0,0 +1,560 @@
+use gen_id_allocator::{Fixed, Id, Valid, ValidId};
+use iter_context::{ContextualIterator, Iter, IterMut};
+use std::iter::StepBy;
+use std::marker::PhantomData;
+use std::ops::{Index, IndexMut};
+
+/// A dense matrix of values indexed by a pair of ids, stored row-major with one row per `A`.
+#[derive(Debug)]
+pub struct PairComponent<A, B, T> {
+    values: Vec<T>,
+    rows: usize,
+    columns: usize,
+    marker: PhantomData<*const (A, B)>,
+}
+
+impl<A, B, T> Default for PairComponent<A, B, T> {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            values: Vec::new(),
+            rows: 0,
+            columns: 0,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<A, B, T: Clone> Clone for PairComponent<A, B, T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            values: self.values.clone(),
+            rows: self.rows,
+            columns: self.columns,
+            marker: PhantomData,
+        }
+    }
+
+    #[inline]
+    fn clone_from(&mut self, source: &Self) {
+        self.values.clone_from(&source.values);
+        self.rows = source.rows;
+        self.columns = source.columns;
+    }
+}
+
+impl<A, B, T> PairComponent<A, B, T> {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Resizes the matrix, keeping existing values at their pair of ids and filling new cells.
+    pub fn resize_with<F: FnMut() -> T>(&mut self, rows: usize, columns: usize, mut fill: F) {
+        if columns == self.columns {
+            self.values.truncate(rows * columns);
+            self.values.resize_with(rows * columns, fill);
+        } else {
+            let mut old = std::mem::take(&mut self.values).into_iter();
+            let kept_rows = rows.min(self.rows);
+            let kept_columns = columns.min(self.columns);
+
+            self.values.reserve_exact(rows * columns);
+            for _ in 0..kept_rows {
+                self.values.extend(old.by_ref().take(kept_columns));
+                old.by_ref()
+                    .take(self.columns - kept_columns)
+                    .for_each(drop);
+                self.values
+                    .extend(std::iter::repeat_with(&mut fill).take(columns - kept_columns));
+            }
+            let remaining = rows * columns - self.values.len();
+            self.values
+                .extend(std::iter::repeat_with(fill).take(remaining));
+        }
+
+        self.rows = rows;
+        self.columns = columns;
+    }
+
+    #[inline]
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    #[inline]
+    pub fn columns(&self) -> usize {
+        self.columns
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    #[inline]
+    fn index_of(&self, a: Id<A>, b: Id<B>) -> Option<usize> {
+        let (row, column) = (a.untyped.index(), b.untyped.index());
+        if row < self.rows && column < self.columns {
+            Some(row * self.columns + column)
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    fn expect_index(&self, a: Id<A>, b: Id<B>) -> usize {
+        self.index_of(a, b).expect("Invalid index")
+    }
+
+    #[inline]
+    pub fn get<IdA, IdB>(&self, a: IdA, b: IdB) -> Option<&T>
+    where
+        IdA: ValidId<Arena = A>,
+        IdB: ValidId<Arena = B>,
+    {
+        let index = self.index_of(a.id(), b.id())?;
+        self.values.get(index)
+    }
+
+    #[inline]
+    pub fn get_mut<IdA, IdB>(&mut self, a: IdA, b: IdB) -> Option<&mut T>
+    where
+        IdA: ValidId<Arena = A>,
+        IdB: ValidId<Arena = B>,
+    {
+        let index = self.index_of(a.id(), b.id())?;
+        self.values.get_mut(index)
+    }
+
+    /// The values for a single `A`, as a contextual iterator over `B`.
+    #[inline]
+    pub fn row<IdA: ValidId<Arena = A>>(&self, a: IdA) -> Iter<B, T> {
+        let row = a.id().untyped.index();
+        assert!(row < self.rows, "Invalid index");
+
+        let start = row * self.columns;
+        Iter::new(self.values[start..start + self.columns].iter())
+    }
+
+    #[inline]
+    pub fn row_mut<IdA: ValidId<Arena = A>>(&mut self, a: IdA) -> IterMut<B, T> {
+        let row = a.id().untyped.index();
+        assert!(row < self.rows, "Invalid index");
+
+        let start = row * self.columns;
+        IterMut::new(self.values[start..start + self.columns].iter_mut())
+    }
+
+    /// The values for a single `B`, as a contextual iterator over `A`.
+    #[inline]
+    pub fn column<IdB: ValidId<Arena = B>>(&self, b: IdB) -> Column<A, T> {
+        let column = b.id().untyped.index();
+        assert!(column < self.columns, "Invalid index");
+
+        Column {
+            iter: self
+                .values
+                .get(column..)
+                .unwrap_or(&[])
+                .iter()
+                .step_by(self.columns),
+            marker: PhantomData,
+        }
+    }
+
+    #[inline]
+    pub fn iter(&self) -> std::slice::Iter<T> {
+        self.values.iter()
+    }
+
+    #[inline]
+    pub fn iter_mut(&mut self) -> std::slice::IterMut<T> {
+        self.values.iter_mut()
+    }
+}
+
+impl<A, B, T: Default> PairComponent<A, B, T> {
+    #[inline]
+    pub fn with_size(rows: usize, columns: usize) -> Self {
+        let mut component = Self::default();
+        component.resize(rows, columns);
+        component
+    }
+
+    #[inline]
+    pub fn resize(&mut self, rows: usize, columns: usize) {
+        self.resize_with(rows, columns, Default::default);
+    }
+}
+
+/// A strided view of one column of a [`PairComponent`].
+#[derive(Debug)]
+pub struct Column<'a, Arena, T> {
+    iter: StepBy<std::slice::Iter<'a, T>>,
+    marker: PhantomData<*const Arena>,
+}
+
+impl<'a, Arena, T> Iterator for Column<'a, Arena, T> {
+    type Item = &'a T;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<'a, Arena, T> ContextualIterator for Column<'a, Arena, T> {
+    type Context = Arena;
+}
+
+impl<A: Fixed, B: Fixed, T> Index<(Id<A>, Id<B>)> for PairComponent<A, B, T> {
+    type Output = T;
+
+    #[inline]
+    fn index(&self, (a, b): (Id<A>, Id<B>)) -> &Self::Output {
+        &self.values[self.expect_index(a, b)]
+    }
+}
+
+impl<A: Fixed, B: Fixed, T> IndexMut<(Id<A>, Id<B>)> for PairComponent<A, B, T> {
+    #[inline]
+    fn index_mut(&mut self, (a, b): (Id<A>, Id<B>)) -> &mut Self::Output {
+        let index = self.expect_index(a, b);
+        &mut self.values[index]
+    }
+}
+
+impl<'va, 'vb, A, B, T> Index<(Valid<'va, Id<A>>, Valid<'vb, Id<B>>)> for PairComponent<A, B, T> {
+    type Output = T;
+
+    #[inline]
+    fn index(&self, (a, b): (Valid<'va, Id<A>>, Valid<'vb, Id<B>>)) -> &Self::Output {
+        &self.values[self.expect_index(a.id(), b.id())]
+    }
+}
+
+impl<'va, 'vb, A, B, T> IndexMut<(Valid<'va, Id<A>>, Valid<'vb, Id<B>>)>
+    for PairComponent<A, B, T>
+{
+    #[inline]
+    fn index_mut(&mut self, (a, b): (Valid<'va, Id<A>>, Valid<'vb, Id<B>>)) -> &mut Self::Output {
+        let index = self.expect_index(a.id(), b.id());
+        &mut self.values[index]
+    }
+}
+
+/// A dense matrix for relations where `(a, b)` and `(b, a)` share a value.
+///
+/// Only the upper triangle, including the diagonal, is stored.
+#[derive(Debug)]
+pub struct SymmetricPairComponent<Arena, T> {
+    values: Vec<T>,
+    size: usize,
+    marker: PhantomData<*const Arena>,
+}
+
+impl<Arena, T> Default for SymmetricPairComponent<Arena, T> {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            values: Vec::new(),
+            size: 0,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<Arena, T: Clone> Clone for SymmetricPairComponent<Arena, T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            values: self.values.clone(),
+            size: self.size,
+            marker: PhantomData,
+        }
+    }
+
+    #[inline]
+    fn clone_from(&mut self, source: &Self) {
+        self.values.clone_from(&source.values);
+        self.size = source.size;
+    }
+}
+
+#[inline]
+fn triangle(n: usize) -> usize {
+    n * (n + 1) / 2
+}
+
+impl<Arena, T> SymmetricPairComponent<Arena, T> {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Resizes the matrix to `size` ids per side, keeping existing values and filling new cells.
+    #[inline]
+    pub fn resize_with<F: FnMut() -> T>(&mut self, size: usize, fill: F) {
+        self.values.truncate(triangle(size));
+        self.values.resize_with(triangle(size), fill);
+        self.size = size;
+    }
+
+    #[inline]
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    #[inline]
+    fn index_of(&self, a: Id<Arena>, b: Id<Arena>) -> Option<usize> {
+        let (a, b) = (a.untyped.index(), b.untyped.index());
+        let (low, high) = if a <= b { (a, b) } else { (b, a) };
+        if high < self.size {
+            Some(triangle(high) + low)
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    fn expect_index(&self, a: Id<Arena>, b: Id<Arena>) -> usize {
+        self.index_of(a, b).expect("Invalid index")
+    }
+
+    #[inline]
+    pub fn get<IdA, IdB>(&self, a: IdA, b: IdB) -> Option<&T>
+    where
+        IdA: ValidId<Arena = Arena>,
+        IdB: ValidId<Arena = Arena>,
+    {
+        let index = self.index_of(a.id(), b.id())?;
+        self.values.get(index)
+    }
+
+    #[inline]
+    pub fn get_mut<IdA, IdB>(&mut self, a: IdA, b: IdB) -> Option<&mut T>
+    where
+        IdA: ValidId<Arena = Arena>,
+        IdB: ValidId<Arena = Arena>,
+    {
+        let index = self.index_of(a.id(), b.id())?;
+        self.values.get_mut(index)
+    }
+
+    /// The values paired with a single id, as a contextual iterator over the same arena.
+    #[inline]
+    pub fn row<Id: ValidId<Arena = Arena>>(&self, id: Id) -> SymmetricRow<Arena, T> {
+        let row = id.id().untyped.index();
+        assert!(row < self.size, "Invalid index");
+
+        SymmetricRow {
+            values: &self.values,
+            row,
+            column: 0,
+            size: self.size,
+            marker: PhantomData,
+        }
+    }
+
+    #[inline]
+    pub fn iter(&self) -> std::slice::Iter<T> {
+        self.values.iter()
+    }
+
+    #[inline]
+    pub fn iter_mut(&mut self) -> std::slice::IterMut<T> {
+        self.values.iter_mut()
+    }
+}
+
+impl<Arena, T: Default> SymmetricPairComponent<Arena, T> {
+    #[inline]
+    pub fn with_size(size: usize) -> Self {
+        let mut component = Self::default();
+        component.resize(size);
+        component
+    }
+
+    #[inline]
+    pub fn resize(&mut self, size: usize) {
+        self.resize_with(size, Default::default);
+    }
+}
+
+/// One row of a [`SymmetricPairComponent`], read across both halves of the triangle.
+#[derive(Debug)]
+pub struct SymmetricRow<'a, Arena, T> {
+    values: &'a [T],
+    row: usize,
+    column: usize,
+    size: usize,
+    marker: PhantomData<*const Arena>,
+}
+
+impl<'a, Arena, T> Iterator for SymmetricRow<'a, Arena, T> {
+    type Item = &'a T;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.column == self.size {
+            return None;
+        }
+
+        let (low, high) = if self.row <= self.column {
+            (self.row, self.column)
+        } else {
+            (self.column, self.row)
+        };
+        self.column += 1;
+
+        self.values.get(triangle(high) + low)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.size - self.column;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<'a, Arena, T> ContextualIterator for SymmetricRow<'a, Arena, T> {
+    type Context = Arena;
+}
+
+impl<Arena: Fixed, T> Index<(Id<Arena>, Id<Arena>)> for SymmetricPairComponent<Arena, T> {
+    type Output = T;
+
+    #[inline]
+    fn index(&self, (a, b): (Id<Arena>, Id<Arena>)) -> &Self::Output {
+        &self.values[self.expect_index(a, b)]
+    }
+}
+
+impl<Arena: Fixed, T> IndexMut<(Id<Arena>, Id<Arena>)> for SymmetricPairComponent<Arena, T> {
+    #[inline]
+    fn index_mut(&mut self, (a, b): (Id<Arena>, Id<Arena>)) -> &mut Self::Output {
+        let index = self.expect_index(a, b);
+        &mut self.values[index]
+    }
+}
+
+impl<'va, 'vb, Arena, T> Index<(Valid<'va, Id<Arena>>, Valid<'vb, Id<Arena>>)>
+    for SymmetricPairComponent<Arena, T>
+{
+    type Output = T;
+
+    #[inline]
+    fn index(&self, (a, b): (Valid<'va, Id<Arena>>, Valid<'vb, Id<Arena>>)) -> &Self::Output {
+        &self.values[self.expect_index(a.id(), b.id())]
+    }
+}
+
+impl<'va, 'vb, Arena, T> IndexMut<(Valid<'va, Id<Arena>>, Valid<'vb, Id<Arena>>)>
+    for SymmetricPairComponent<Arena, T>
+{
+    #[inline]
+    fn index_mut(
+        &mut self,
+        (a, b): (Valid<'va, Id<Arena>>, Valid<'vb, Id<Arena>>),
+    ) -> &mut Self::Output {
+        let index = self.expect_index(a.id(), b.id());
+        &mut self.values[index]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_util::valid;
+
+    struct Faction;
+    struct System;
+
+    #[test]
+    fn resize_keeps_values_at_their_pair() {
+        let mut pairs = PairComponent::<Faction, System, u32>::with_size(2, 2);
+        pairs[(valid(0), valid(1))] = 1;
+        pairs[(valid(1), valid(0))] = 2;
+
+        pairs.resize(3, 3);
+
+        assert_eq!(Some(&1), pairs.get(valid(0), valid(1)));
+        assert_eq!(Some(&2), pairs.get(valid(1), valid(0)));
+        assert_eq!(None, pairs.get(valid(3), valid(0)));
+        assert_eq!(9, pairs.len());
+    }
+
+    #[test]
+    fn row_and_column_views() {
+        let mut pairs = PairComponent::<Faction, System, u32>::with_size(2, 3);
+        pairs
+            .iter_mut()
+            .enumerate()
+            .for_each(|(i, v)| *v = i as u32);
+
+        let row = pairs.row(valid(1)).into_iter().copied().collect::<Vec<_>>();
+        let column = pairs.column(valid(1)).copied().collect::<Vec<_>>();
+
+        assert_eq!(vec![3, 4, 5], row);
+        assert_eq!(vec![1, 4], column);
+    }
+
+    #[test]
+    fn column_of_empty_table() {
+        let pairs = PairComponent::<Faction, System, u32>::with_size(0, 3);
+
+        assert_eq!(0, pairs.column(valid(1)).count());
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid index")]
+    fn row_out_of_range_panics() {
+        let pairs = PairComponent::<Faction, System, u32>::with_size(2, 3);
+
+        pairs.row(valid(2));
+    }
+
+    #[test]
+    fn symmetric_pairs_share_a_value() {
+        let mut pairs = SymmetricPairComponent::<System, u32>::with_size(3);
+
+        pairs[(valid(2), valid(0))] = 7;
+
+        assert_eq!(Some(&7), pairs.get(valid(0), valid(2)));
+        assert_eq!(6, pairs.len());
+    }
+
+    #[test]
+    fn symmetric_row_reads_both_halves() {
+        let mut pairs = SymmetricPairComponent::<System, u32>::with_size(3);
+        pairs[(valid(0), valid(1))] = 1;
+        pairs[(valid(1), valid(1))] = 2;
+        pairs[(valid(1), valid(2))] = 3;
+
+        let row = pairs.row(valid(1)).copied().collect::<Vec<_>>();
+
+        assert_eq!(vec![1, 2, 3], row);
+    }
+}
@@ -0,0 +1,182 @@
+use crate::component::Component;
+use std::marker::PhantomData;
+
+/// The changes that bring a component from one state to another: its new length and the new value
+/// of every id index that differs or did not exist before.
+#[derive(Debug)]
+pub struct Patch<Arena, T> {
+    from_len: usize,
+    to_len: usize,
+    changes: Vec<(usize, T)>,
+    marker: PhantomData<*const Arena>,
+}
+
+impl<Arena, T: Clone> Clone for Patch<Arena, T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            from_len: self.from_len,
+            to_len: self.to_len,
+            changes: self.changes.clone(),
+            marker: PhantomData,
+        }
+    }
+
+    #[inline]
+    fn clone_from(&mut self, source: &Self) {
+        self.from_len = source.from_len;
+        self.to_len = source.to_len;
+        self.changes.clone_from(&source.changes);
+    }
+}
+
+impl<Arena, T: PartialEq> PartialEq for Patch<Arena, T> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.from_len == other.from_len
+            && self.to_len == other.to_len
+            && self.changes == other.changes
+    }
+}
+
+impl<Arena, T> Patch<Arena, T> {
+    #[inline]
+    pub(crate) fn from_parts(from_len: usize, to_len: usize, changes: Vec<(usize, T)>) -> Self {
+        Self {
+            from_len,
+            to_len,
+            changes,
+            marker: PhantomData,
+        }
+    }
+
+    /// The length of the component the patch applies to.
+    #[inline]
+    pub fn from_len(&self) -> usize {
+        self.from_len
+    }
+
+    /// The length of the component after the patch is applied.
+    #[inline]
+    pub fn to_len(&self) -> usize {
+        self.to_len
+    }
+
+    /// The changed id indices with their new values, in ascending order.
+    #[inline]
+    pub fn changes(&self) -> impl Iterator<Item = (usize, &T)> {
+        self.changes.iter().map(|(index, value)| (*index, value))
+    }
+
+    /// The changed id indices, in ascending order.
+    #[inline]
+    pub fn indices(&self) -> impl Iterator<Item = usize> + '_ {
+        self.changes.iter().map(|(index, _)| *index)
+    }
+
+    /// True if applying the patch would not change anything.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty() && self.from_len == self.to_len
+    }
+}
+
+impl<Arena, T: PartialEq + Clone> Component<Arena, T> {
+    /// Compares two states of a component and records what changed between them.
+    pub fn diff(old: &Self, new: &Self) -> Patch<Arena, T> {
+        let old_values = old.as_slice();
+        let changes = new
+            .as_slice()
+            .iter()
+            .enumerate()
+            .filter(|(index, value)| old_values.get(*index) != Some(*value))
+            .map(|(index, value)| (index, value.clone()))
+            .collect();
+
+        Patch {
+            from_len: old.len(),
+            to_len: new.len(),
+            changes,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<Arena, T: Clone> Component<Arena, T> {
+    /// Brings the component from the old state of the patch to its new state.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the component's length does not match the length the patch was made from.
+    pub fn apply(&mut self, patch: &Patch<Arena, T>) {
+        assert_eq!(
+            self.len(),
+            patch.from_len,
+            "Patch does not match component length"
+        );
+
+        let split = patch
+            .changes
+            .partition_point(|(index, _)| *index < patch.from_len);
+        let (existing, appended) = patch.changes.split_at(split);
+
+        let mut appended = appended.iter().map(|(_, value)| value.clone());
+        self.resize_with(patch.to_len, || {
+            appended.next().expect("Patch is missing a value")
+        });
+
+        let values = self.as_mut_slice();
+        for (index, value) in existing {
+            values[*index].clone_from(value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_util::values;
+
+    #[test]
+    fn diff_records_changed_ids() {
+        let old = Component::<(), u32>::from(vec![1, 2, 3]);
+        let new = Component::<(), u32>::from(vec![1, 5, 3]);
+
+        let patch = Component::diff(&old, &new);
+
+        assert_eq!(vec![(1, &5)], patch.changes().collect::<Vec<_>>());
+        assert!(Component::diff(&old, &old).is_empty());
+    }
+
+    #[test]
+    fn apply_grows_component() {
+        let mut old = Component::<(), u32>::from(vec![1, 2]);
+        let new = Component::<(), u32>::from(vec![0, 2, 3, 4]);
+
+        let patch = Component::diff(&old, &new);
+        old.apply(&patch);
+
+        assert_eq!(vec![0, 2, 3, 4], values(&old));
+    }
+
+    #[test]
+    fn apply_shrinks_component() {
+        let mut old = Component::<(), u32>::from(vec![1, 2, 3]);
+        let new = Component::<(), u32>::from(vec![4]);
+
+        let patch = Component::diff(&old, &new);
+        old.apply(&patch);
+
+        assert_eq!(vec![4], values(&old));
+    }
+
+    #[test]
+    #[should_panic(expected = "Patch does not match component length")]
+    fn apply_to_wrong_length_panics() {
+        let old = Component::<(), u32>::from(vec![1]);
+        let new = Component::<(), u32>::from(vec![2]);
+        let patch = Component::diff(&old, &new);
+
+        Component::<(), u32>::default().apply(&patch);
+    }
+}
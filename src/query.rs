@@ -0,0 +1,285 @@
+use crate::component::Component;
+use crate::raw_component::RawComponent;
+
+/// A column that can take part in a [`query`].
+pub trait Fetch {
+    type Arena;
+    type Iter: ExactSizeIterator;
+    type Item;
+
+    fn fetch(self) -> Self::Iter;
+
+    /// Returns `None` if the id should be skipped.
+    fn filter(item: <Self::Iter as Iterator>::Item) -> Option<Self::Item>;
+}
+
+/// Wraps an `Option`-valued component so that ids with a `None` value are skipped by a query.
+#[derive(Debug)]
+pub struct Required<C>(pub C);
+
+macro_rules! impl_fetch {
+    ($component:ident) => {
+        impl<'a, Arena, T> Fetch for &'a $component<Arena, T> {
+            type Arena = Arena;
+            type Iter = std::slice::Iter<'a, T>;
+            type Item = &'a T;
+
+            #[inline]
+            fn fetch(self) -> Self::Iter {
+                self.into_iter()
+            }
+
+            #[inline]
+            fn filter(item: &'a T) -> Option<Self::Item> {
+                Some(item)
+            }
+        }
+
+        impl<'a, Arena, T> Fetch for &'a mut $component<Arena, T> {
+            type Arena = Arena;
+            type Iter = std::slice::IterMut<'a, T>;
+            type Item = &'a mut T;
+
+            #[inline]
+            fn fetch(self) -> Self::Iter {
+                self.into_iter()
+            }
+
+            #[inline]
+            fn filter(item: &'a mut T) -> Option<Self::Item> {
+                Some(item)
+            }
+        }
+
+        impl<'a, Arena, T> Fetch for Required<&'a $component<Arena, Option<T>>> {
+            type Arena = Arena;
+            type Iter = std::slice::Iter<'a, Option<T>>;
+            type Item = &'a T;
+
+            #[inline]
+            fn fetch(self) -> Self::Iter {
+                self.0.into_iter()
+            }
+
+            #[inline]
+            fn filter(item: &'a Option<T>) -> Option<Self::Item> {
+                item.as_ref()
+            }
+        }
+
+        impl<'a, Arena, T> Fetch for Required<&'a mut $component<Arena, Option<T>>> {
+            type Arena = Arena;
+            type Iter = std::slice::IterMut<'a, Option<T>>;
+            type Item = &'a mut T;
+
+            #[inline]
+            fn fetch(self) -> Self::Iter {
+                self.0.into_iter()
+            }
+
+            #[inline]
+            fn filter(item: &'a mut Option<T>) -> Option<Self::Item> {
+                item.as_mut()
+            }
+        }
+    };
+}
+
+impl_fetch!(Component);
+impl_fetch!(RawComponent);
+
+/// A tuple of columns over the same arena.
+pub trait Join {
+    type Arena;
+    type Iters;
+    type Item;
+
+    fn iters(self) -> Self::Iters;
+
+    /// Returns the next row that passes every filter, together with its index. `index` counts the
+    /// rows consumed so far, including skipped ones.
+    fn next(iters: &mut Self::Iters, index: &mut usize) -> Option<(usize, Self::Item)>;
+}
+
+macro_rules! impl_join {
+    ($first:ident $(, $rest:ident)*) => {
+        #[allow(non_snake_case)]
+        impl<$first: Fetch, $($rest: Fetch<Arena = $first::Arena>,)*> Join for ($first, $($rest,)*) {
+            type Arena = $first::Arena;
+            type Iters = ($first::Iter, $($rest::Iter,)*);
+            type Item = ($first::Item, $($rest::Item,)*);
+
+            #[inline]
+            fn iters(self) -> Self::Iters {
+                let ($first, $($rest,)*) = self;
+                let iters = ($first.fetch(), $($rest.fetch(),)*);
+                {
+                    #[allow(unused_variables)]
+                    let ($first, $($rest,)*) = &iters;
+                    $(
+                        assert_eq!(
+                            $first.len(),
+                            $rest.len(),
+                            "Query columns have different lengths"
+                        );
+                    )*
+                }
+                iters
+            }
+
+            #[inline]
+            fn next(iters: &mut Self::Iters, index: &mut usize) -> Option<(usize, Self::Item)> {
+                let ($first, $($rest,)*) = iters;
+                loop {
+                    let row = ($first::filter($first.next()?), $($rest::filter($rest.next()?),)*);
+                    *index += 1;
+                    if let (Some($first), $(Some($rest),)*) = row {
+                        return Some((*index - 1, ($first, $($rest,)*)));
+                    }
+                }
+            }
+        }
+    };
+}
+
+impl_join!(A);
+impl_join!(A, B);
+impl_join!(A, B, C);
+impl_join!(A, B, C, D);
+impl_join!(A, B, C, D, E);
+impl_join!(A, B, C, D, E, F);
+impl_join!(A, B, C, D, E, F, G);
+impl_join!(A, B, C, D, E, F, G, H);
+
+/// An iterator over the rows of a [`Join`].
+pub struct Query<J: Join> {
+    iters: J::Iters,
+    index: usize,
+}
+
+impl<J: Join> Query<J> {
+    /// Yields the index of each row's id along with the row, so that rows can still be matched to
+    /// their ids when a [`Required`] column skips some of them.
+    #[inline]
+    pub fn indexed(self) -> IndexedQuery<J> {
+        IndexedQuery { query: self }
+    }
+}
+
+impl<J: Join> Iterator for Query<J> {
+    type Item = J::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        J::next(&mut self.iters, &mut self.index).map(|(_, item)| item)
+    }
+}
+
+/// An iterator over the rows of a [`Join`] and the index of the id of each row.
+pub struct IndexedQuery<J: Join> {
+    query: Query<J>,
+}
+
+impl<J: Join> Iterator for IndexedQuery<J> {
+    type Item = (usize, J::Item);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        J::next(&mut self.query.iters, &mut self.query.index)
+    }
+}
+
+/// Iterates a tuple of same-arena components together, skipping ids where a [`Required`] column is `None`.
+///
+/// All columns must have the same length, which is checked when the query is created. Use
+/// [`Query::indexed`] to find out which id each row belongs to, or join a component of ids.
+///
+/// Components over different arenas are rejected at compile time:
+///
+/// ```compile_fail
+/// use gen_id_component::{query, Component};
+///
+/// struct Ships;
+/// struct Planets;
+///
+/// let ships = Component::<Ships, u32>::default();
+/// let planets = Component::<Planets, u32>::default();
+///
+/// for _ in query((&ships, &planets)) {}
+/// ```
+///
+/// # Panics
+///
+/// Panics if the columns have different lengths.
+#[inline]
+pub fn query<J: Join>(join: J) -> Query<J> {
+    Query {
+        iters: join.iters(),
+        index: 0,
+    }
+}
+
+/// Shorthand for [`query`] that accepts the columns without wrapping them in a tuple.
+#[macro_export]
+macro_rules! query {
+    ($($column:expr),+ $(,)?) => {
+        $crate::query(($($column,)+))
+    };
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn join_mixed_columns() {
+        let mut position = Component::<(), u32>::from(vec![0, 0, 0]);
+        let velocity = RawComponent::<(), u32>::from(vec![1, 2, 3]);
+        let target = Component::<(), Option<u32>>::from(vec![Some(10), None, Some(30)]);
+
+        for (p, v, t) in query!(&mut position, &velocity, &target) {
+            *p = v + t.unwrap_or_default();
+        }
+
+        assert_eq!(
+            vec![11, 2, 33],
+            position.into_iter().copied().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn required_skips_none() {
+        let mut position = Component::<(), u32>::from(vec![0, 0, 0]);
+        let target = Component::<(), Option<u32>>::from(vec![Some(10), None, Some(30)]);
+
+        for (p, t) in query((&mut position, Required(&target))) {
+            *p = *t;
+        }
+
+        assert_eq!(
+            vec![10, 0, 30],
+            position.into_iter().copied().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn indexed_rows_skip_none() {
+        let target = Component::<(), Option<u32>>::from(vec![None, Some(10), None, Some(30)]);
+
+        let rows = query((Required(&target),))
+            .indexed()
+            .map(|(index, (t,))| (index, *t))
+            .collect::<Vec<_>>();
+
+        assert_eq!(vec![(1, 10), (3, 30)], rows);
+    }
+
+    #[test]
+    #[should_panic(expected = "Query columns have different lengths")]
+    fn mismatched_lengths_panic() {
+        let a = Component::<(), u32>::from(vec![1, 2, 3]);
+        let b = Component::<(), u32>::from(vec![1, 2]);
+
+        query((&a, &b)).for_each(drop);
+    }
+}
@@ -1,19 +1,31 @@
+use crate::index_vec::IndexVec;
+use crate::storage::Storage;
 use crate::untyped_component::UntypedComponent;
-use force_derive::ForceDefault;
 use gen_id_allocator::{Fixed, Id, IdRange};
 use iter_context::{ContextualIterator, FromContextualIterator, Iter, IterMut};
 use ref_cast::RefCast;
+use std::iter::FromIterator;
 use std::marker::PhantomData;
 use std::ops::{Index, IndexMut};
 
 #[repr(transparent)]
-#[derive(Debug, ForceDefault, RefCast)]
-pub struct RawComponent<Arena, T> {
-    values: UntypedComponent<T>,
+#[derive(Debug, RefCast)]
+pub struct RawComponent<Arena, T, S = IndexVec<T>> {
+    values: UntypedComponent<T, S>,
     marker: PhantomData<*const Arena>,
 }
 
-impl<Arena, T: Clone> Clone for RawComponent<Arena, T> {
+impl<Arena, T, S: Default> Default for RawComponent<Arena, T, S> {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            values: Default::default(),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<Arena, T, S: Clone> Clone for RawComponent<Arena, T, S> {
     #[inline]
     fn clone(&self) -> Self {
         Self {
@@ -31,6 +43,13 @@ impl<Arena, T: Clone> Clone for RawComponent<Arena, T> {
 impl<Arena, T> From<Vec<T>> for RawComponent<Arena, T> {
     #[inline]
     fn from(values: Vec<T>) -> Self {
+        IndexVec::from(values).into()
+    }
+}
+
+impl<Arena, T, S: Storage<T>> From<S> for RawComponent<Arena, T, S> {
+    #[inline]
+    fn from(values: S) -> Self {
         RawComponent {
             values: values.into(),
             marker: PhantomData,
@@ -38,7 +57,7 @@ impl<Arena, T> From<Vec<T>> for RawComponent<Arena, T> {
     }
 }
 
-impl<Arena, T> RawComponent<Arena, T> {
+impl<Arena, T, S: Storage<T>> RawComponent<Arena, T, S> {
     #[inline]
     pub fn insert(&mut self, id: Id<Arena>, value: T) {
         self.insert_with(id, value, || panic!());
@@ -64,33 +83,60 @@ impl<Arena, T> RawComponent<Arena, T> {
         self.values.swap(a.untyped, b.untyped);
     }
 
+    #[inline]
+    pub fn resize_with<F: FnMut() -> T>(&mut self, len: usize, f: F) {
+        self.values.resize_with(len, f);
+    }
+
     #[inline]
     pub fn fill_with<F: FnMut() -> T>(&mut self, f: F) {
         self.values.fill_with(f);
     }
 
     #[inline]
-    pub fn iter(&self) -> Iter<Arena, T> {
-        Iter::new(self.values.iter())
+    pub fn len(&self) -> usize {
+        self.values.len()
     }
 
     #[inline]
-    pub fn iter_mut(&mut self) -> IterMut<Arena, T> {
-        IterMut::new(self.values.iter_mut())
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
     }
 
     #[inline]
-    pub fn len(&self) -> usize {
-        self.values.len()
+    pub(crate) fn storage(&self) -> &S {
+        self.values.storage()
     }
 
     #[inline]
-    pub fn is_empty(&self) -> bool {
-        self.len() == 0
+    pub(crate) fn storage_mut(&mut self) -> &mut S {
+        self.values.storage_mut()
+    }
+}
+
+impl<Arena, T> RawComponent<Arena, T> {
+    #[inline]
+    pub(crate) fn as_slice(&self) -> &[T] {
+        self.values.as_slice()
+    }
+
+    #[inline]
+    pub(crate) fn as_mut_slice(&mut self) -> &mut [T] {
+        self.values.as_mut_slice()
+    }
+
+    #[inline]
+    pub fn iter(&self) -> Iter<Arena, T> {
+        Iter::new(self.values.iter())
+    }
+
+    #[inline]
+    pub fn iter_mut(&mut self) -> IterMut<Arena, T> {
+        IterMut::new(self.values.iter_mut())
     }
 }
 
-impl<Arena, T> RawComponent<Arena, Option<T>> {
+impl<Arena, T, S: Storage<Option<T>>> RawComponent<Arena, Option<T>, S> {
     #[inline]
     pub fn remove(&mut self, id: Id<Arena>) -> Option<T> {
         let value = self.values.get_mut(id.untyped)?;
@@ -98,7 +144,7 @@ impl<Arena, T> RawComponent<Arena, Option<T>> {
     }
 }
 
-impl<'a, Arena, T: Copy + 'a> RawComponent<Arena, T> {
+impl<'a, Arena, T: Copy + 'a, S: Storage<T>> RawComponent<Arena, T, S> {
     #[inline]
     pub fn assign<Rhs: ContextualIterator<Context = Arena, Item = T>>(&mut self, rhs: Rhs) {
         self.zip(rhs).for_each(|(lhs, rhs)| *lhs = rhs);
@@ -110,7 +156,7 @@ impl<'a, Arena, T: Copy + 'a> RawComponent<Arena, T> {
     }
 }
 
-impl<Arena, T> Index<Id<Arena>> for RawComponent<Arena, T> {
+impl<Arena, T, S: Storage<T>> Index<Id<Arena>> for RawComponent<Arena, T, S> {
     type Output = T;
 
     #[inline]
@@ -119,14 +165,14 @@ impl<Arena, T> Index<Id<Arena>> for RawComponent<Arena, T> {
     }
 }
 
-impl<Arena, T> IndexMut<Id<Arena>> for RawComponent<Arena, T> {
+impl<Arena, T, S: Storage<T>> IndexMut<Id<Arena>> for RawComponent<Arena, T, S> {
     #[inline]
     fn index_mut(&mut self, index: Id<Arena>) -> &mut Self::Output {
         self.values.index_mut(index.untyped)
     }
 }
 
-impl<Arena, T> Index<&Id<Arena>> for RawComponent<Arena, T> {
+impl<Arena, T, S: Storage<T>> Index<&Id<Arena>> for RawComponent<Arena, T, S> {
     type Output = T;
 
     #[inline]
@@ -135,7 +181,7 @@ impl<Arena, T> Index<&Id<Arena>> for RawComponent<Arena, T> {
     }
 }
 
-impl<Arena, T> IndexMut<&Id<Arena>> for RawComponent<Arena, T> {
+impl<Arena, T, S: Storage<T>> IndexMut<&Id<Arena>> for RawComponent<Arena, T, S> {
     #[inline]
     fn index_mut(&mut self, index: &Id<Arena>) -> &mut Self::Output {
         self.values.index_mut(index.untyped)
@@ -156,9 +202,9 @@ impl<Arena: Fixed, T> IndexMut<IdRange<Arena>> for RawComponent<Arena, T> {
     }
 }
 
-impl<'a, Arena, T> IntoIterator for &'a RawComponent<Arena, T> {
+impl<'a, Arena, T, S: Storage<T>> IntoIterator for &'a RawComponent<Arena, T, S> {
     type Item = &'a T;
-    type IntoIter = std::slice::Iter<'a, T>;
+    type IntoIter = S::Iter<'a>;
 
     #[inline]
     fn into_iter(self) -> Self::IntoIter {
@@ -166,9 +212,9 @@ impl<'a, Arena, T> IntoIterator for &'a RawComponent<Arena, T> {
     }
 }
 
-impl<'a, Arena, T> IntoIterator for &'a mut RawComponent<Arena, T> {
+impl<'a, Arena, T, S: Storage<T>> IntoIterator for &'a mut RawComponent<Arena, T, S> {
     type Item = &'a mut T;
-    type IntoIter = std::slice::IterMut<'a, T>;
+    type IntoIter = S::IterMut<'a>;
 
     #[inline]
     fn into_iter(self) -> Self::IntoIter {
@@ -176,15 +222,17 @@ impl<'a, Arena, T> IntoIterator for &'a mut RawComponent<Arena, T> {
     }
 }
 
-impl<'a, Arena, T> ContextualIterator for &'a RawComponent<Arena, T> {
+impl<'a, Arena, T, S: Storage<T>> ContextualIterator for &'a RawComponent<Arena, T, S> {
     type Context = Arena;
 }
 
-impl<'a, Arena, T> ContextualIterator for &'a mut RawComponent<Arena, T> {
+impl<'a, Arena, T, S: Storage<T>> ContextualIterator for &'a mut RawComponent<Arena, T, S> {
     type Context = Arena;
 }
 
-impl<Arena, T> FromContextualIterator<T> for RawComponent<Arena, T> {
+impl<Arena, T, S: Storage<T> + FromIterator<T>> FromContextualIterator<T>
+    for RawComponent<Arena, T, S>
+{
     type Context = Arena;
 
     #[inline]
@@ -192,11 +240,13 @@ impl<Arena, T> FromContextualIterator<T> for RawComponent<Arena, T> {
     where
         Iter: ContextualIterator<Context = Self::Context, Item = T>,
     {
-        iter.into_iter().collect::<Vec<_>>().into()
+        iter.into_iter().collect::<S>().into()
     }
 }
 
-impl<'a, Arena, T: 'a + Copy> FromContextualIterator<&'a T> for RawComponent<Arena, T> {
+impl<'a, Arena, T: 'a + Copy, S: Storage<T> + FromIterator<T>> FromContextualIterator<&'a T>
+    for RawComponent<Arena, T, S>
+{
     type Context = Arena;
 
     #[inline]
@@ -204,6 +254,6 @@ impl<'a, Arena, T: 'a + Copy> FromContextualIterator<&'a T> for RawComponent<Are
     where
         Iter: ContextualIterator<Context = Self::Context, Item = &'a T>,
     {
-        iter.into_iter().copied().collect::<Vec<_>>().into()
+        iter.into_iter().copied().collect::<S>().into()
     }
 }
@@ -0,0 +1,212 @@
+use crate::dyn_component::DynComponent;
+use gen_id_allocator::Id;
+use std::any::TypeId;
+use std::collections::HashMap;
+
+/// A set of heterogeneous components over one arena, keyed by component type.
+///
+/// Each component type can only be registered once, so columns that share an element type should
+/// use distinct newtypes.
+pub struct Registry<Arena> {
+    components: HashMap<TypeId, Box<dyn DynComponent<Arena>>>,
+}
+
+impl<Arena> Default for Registry<Arena> {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            components: HashMap::new(),
+        }
+    }
+}
+
+impl<Arena: 'static> std::fmt::Debug for Registry<Arena> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list()
+            .entries(self.components.values().map(|c| c.type_name()))
+            .finish()
+    }
+}
+
+impl<Arena: 'static> Clone for Registry<Arena> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            components: self
+                .components
+                .iter()
+                .map(|(k, c)| (*k, c.clone_box()))
+                .collect(),
+        }
+    }
+
+    fn clone_from(&mut self, source: &Self) {
+        self.components
+            .retain(|k, _| source.components.contains_key(k));
+
+        for (k, c) in &source.components {
+            match self.components.get_mut(k) {
+                Some(component) => component.clone_from_dyn(c.as_ref()),
+                None => {
+                    self.components.insert(*k, c.clone_box());
+                }
+            }
+        }
+    }
+}
+
+impl<Arena: 'static> Registry<Arena> {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a component, returning the previously registered component of the same type.
+    #[inline]
+    pub fn register<C: DynComponent<Arena>>(
+        &mut self,
+        component: C,
+    ) -> Option<Box<dyn DynComponent<Arena>>> {
+        self.components
+            .insert(TypeId::of::<C>(), Box::new(component))
+    }
+
+    #[inline]
+    pub fn unregister<C: DynComponent<Arena>>(&mut self) -> Option<C> {
+        let component = self.components.remove(&TypeId::of::<C>())?;
+        component.into_any().downcast().ok().map(|c| *c)
+    }
+
+    #[inline]
+    pub fn contains<C: DynComponent<Arena>>(&self) -> bool {
+        self.components.contains_key(&TypeId::of::<C>())
+    }
+
+    #[inline]
+    pub fn get<C: DynComponent<Arena>>(&self) -> Option<&C> {
+        self.components
+            .get(&TypeId::of::<C>())?
+            .as_any()
+            .downcast_ref()
+    }
+
+    #[inline]
+    pub fn get_mut<C: DynComponent<Arena>>(&mut self) -> Option<&mut C> {
+        self.components
+            .get_mut(&TypeId::of::<C>())?
+            .as_any_mut()
+            .downcast_mut()
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.components.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.components.is_empty()
+    }
+
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = &dyn DynComponent<Arena>> {
+        self.components.values().map(|c| c.as_ref())
+    }
+
+    #[inline]
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut (dyn DynComponent<Arena> + 'static)> {
+        self.components.values_mut().map(|c| c.as_mut())
+    }
+
+    /// Resizes every component to `len`.
+    #[inline]
+    pub fn resize(&mut self, len: usize) {
+        self.iter_mut().for_each(|c| c.resize(len));
+    }
+
+    /// Resets the value for `id` in every component, e.g. when the id is killed.
+    #[inline]
+    pub fn reset(&mut self, id: Id<Arena>) {
+        self.iter_mut().for_each(|c| c.reset(id));
+    }
+
+    #[inline]
+    pub fn swap(&mut self, a: Id<Arena>, b: Id<Arena>) {
+        self.iter_mut().for_each(|c| c.swap(a, b));
+    }
+
+    /// Returns the type names of any components whose length differs from `len`.
+    #[inline]
+    pub fn mismatched_lengths(&self, len: usize) -> Vec<&'static str> {
+        self.iter()
+            .filter(|c| c.len() != len)
+            .map(|c| c.type_name())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{Component, RawComponent};
+
+    #[derive(Debug, Default, Clone, PartialEq)]
+    struct Position(u32);
+
+    #[derive(Debug, Default, Clone, PartialEq)]
+    struct Name(&'static str);
+
+    fn registry() -> Registry<()> {
+        let mut registry = Registry::new();
+        registry.register(Component::<(), Position>::from(vec![
+            Position(1),
+            Position(2),
+        ]));
+        registry.register(RawComponent::<(), Name>::from(vec![Name("a"), Name("b")]));
+        registry
+    }
+
+    #[test]
+    fn get_by_type() {
+        let registry = registry();
+
+        let position = registry.get::<Component<(), Position>>().unwrap();
+
+        assert_eq!(2, position.len());
+        assert!(registry.get::<Component<(), Name>>().is_none());
+    }
+
+    #[test]
+    fn operations_apply_to_every_component() {
+        let mut registry = registry();
+
+        registry.swap(Id::first(0), Id::first(1));
+        registry.reset(Id::first(0));
+        registry.resize(3);
+
+        let names = registry.get::<RawComponent<(), Name>>().unwrap();
+        assert_eq!(
+            vec![Name(""), Name("a"), Name("")],
+            names.into_iter().cloned().collect::<Vec<_>>()
+        );
+        assert!(registry.mismatched_lengths(3).is_empty());
+    }
+
+    #[test]
+    fn clone_from_matches_source() {
+        let source = registry();
+        let mut target = Registry::new();
+        target.register(Component::<(), u32>::default());
+
+        target.clone_from(&source);
+
+        assert_eq!(2, target.len());
+        assert!(!target.contains::<Component<(), u32>>());
+        assert_eq!(
+            Some(&Position(2)),
+            target
+                .get::<Component<(), Position>>()
+                .and_then(|c| c.into_iter().nth(1))
+        );
+    }
+}
@@ -0,0 +1,313 @@
+use crate::index_vec::IndexVec;
+use gen_id_allocator::{Id, ValidId};
+
+type Edges<A, B, T> = Vec<(Id<A>, Id<B>, T)>;
+
+/// A sparse many-to-many relation between two arenas with a value on each edge.
+///
+/// Edges are stored by the index of `A` and indexed again by the index of `B`, together with the
+/// full ids so that an edge is not inherited by a later id that reuses either index.
+#[derive(Debug)]
+pub struct Relation<A, B, T> {
+    forward: IndexVec<Edges<A, B, T>>,
+    backward: IndexVec<Vec<(Id<A>, Id<B>)>>,
+    len: usize,
+}
+
+impl<A, B, T> Default for Relation<A, B, T> {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            forward: Default::default(),
+            backward: Default::default(),
+            len: 0,
+        }
+    }
+}
+
+impl<A, B, T: Clone> Clone for Relation<A, B, T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            forward: self.forward.clone(),
+            backward: self.backward.clone(),
+            len: self.len,
+        }
+    }
+
+    #[inline]
+    fn clone_from(&mut self, source: &Self) {
+        self.forward.clone_from(&source.forward);
+        self.backward.clone_from(&source.backward);
+        self.len = source.len;
+    }
+}
+
+impl<A, B, T> Relation<A, B, T> {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Inserts an edge, returning the previous value if the edge already existed.
+    pub fn insert<IdA, IdB>(&mut self, a: IdA, b: IdB, value: T) -> Option<T>
+    where
+        IdA: ValidId<Arena = A>,
+        IdB: ValidId<Arena = B>,
+    {
+        let (a, b) = (a.id(), b.id());
+
+        if let Some(current) = self.get_mut_by_id(a, b) {
+            return Some(std::mem::replace(current, value));
+        }
+
+        Self::edges_mut(&mut self.forward, a.untyped.index()).push((a, b, value));
+        Self::edges_mut(&mut self.backward, b.untyped.index()).push((a, b));
+        self.len += 1;
+
+        None
+    }
+
+    #[inline]
+    pub fn remove<IdA, IdB>(&mut self, a: IdA, b: IdB) -> Option<T>
+    where
+        IdA: ValidId<Arena = A>,
+        IdB: ValidId<Arena = B>,
+    {
+        let (a, b) = (a.id(), b.id());
+
+        let edges = self.forward.get_mut(a.untyped.index())?;
+        let position = edges
+            .iter()
+            .position(|(source, target, _)| *source == a && *target == b)?;
+        let (_, _, value) = edges.swap_remove(position);
+
+        if let Some(sources) = self.backward.get_mut(b.untyped.index()) {
+            sources.retain(|edge| *edge != (a, b));
+        }
+        self.len -= 1;
+
+        Some(value)
+    }
+
+    #[inline]
+    pub fn get<IdA, IdB>(&self, a: IdA, b: IdB) -> Option<&T>
+    where
+        IdA: ValidId<Arena = A>,
+        IdB: ValidId<Arena = B>,
+    {
+        self.get_by_id(a.id(), b.id())
+    }
+
+    #[inline]
+    pub fn get_mut<IdA, IdB>(&mut self, a: IdA, b: IdB) -> Option<&mut T>
+    where
+        IdA: ValidId<Arena = A>,
+        IdB: ValidId<Arena = B>,
+    {
+        self.get_mut_by_id(a.id(), b.id())
+    }
+
+    #[inline]
+    pub fn contains<IdA, IdB>(&self, a: IdA, b: IdB) -> bool
+    where
+        IdA: ValidId<Arena = A>,
+        IdB: ValidId<Arena = B>,
+    {
+        self.get(a, b).is_some()
+    }
+
+    /// Every `B` related to the given `A`, with the value on each edge.
+    #[inline]
+    pub fn targets<IdA: ValidId<Arena = A>>(&self, a: IdA) -> impl Iterator<Item = (Id<B>, &T)> {
+        let a = a.id();
+        self.forward
+            .get(a.untyped.index())
+            .into_iter()
+            .flatten()
+            .filter(move |(source, _, _)| *source == a)
+            .map(|(_, b, value)| (*b, value))
+    }
+
+    /// Every `A` related to the given `B`, with the value on each edge.
+    #[inline]
+    pub fn sources<IdB: ValidId<Arena = B>>(&self, b: IdB) -> impl Iterator<Item = (Id<A>, &T)> {
+        let b = b.id();
+        self.backward
+            .get(b.untyped.index())
+            .into_iter()
+            .flatten()
+            .filter(move |(_, target)| *target == b)
+            .filter_map(move |(a, b)| self.get_by_id(*a, *b).map(|value| (*a, value)))
+    }
+
+    /// Removes every edge from the given `A`. Takes a plain `Id` so it can be used after the id is killed.
+    pub fn remove_source(&mut self, a: Id<A>) {
+        let edges = match self.forward.get_mut(a.untyped.index()) {
+            Some(edges) => edges,
+            None => return,
+        };
+
+        let backward = &mut self.backward;
+        let before = edges.len();
+        edges.retain(|(source, b, _)| {
+            if *source != a {
+                return true;
+            }
+            if let Some(sources) = backward.get_mut(b.untyped.index()) {
+                sources.retain(|edge| *edge != (a, *b));
+            }
+            false
+        });
+        self.len -= before - edges.len();
+    }
+
+    /// Removes every edge to the given `B`. Takes a plain `Id` so it can be used after the id is killed.
+    pub fn remove_target(&mut self, b: Id<B>) {
+        let sources = match self.backward.get_mut(b.untyped.index()) {
+            Some(sources) => sources,
+            None => return,
+        };
+
+        let mut removed = Vec::new();
+        sources.retain(|&(a, target)| {
+            if target == b {
+                removed.push(a);
+            }
+            target != b
+        });
+
+        for a in removed {
+            if let Some(edges) = self.forward.get_mut(a.untyped.index()) {
+                let before = edges.len();
+                edges.retain(|(source, target, _)| *source != a || *target != b);
+                self.len -= before - edges.len();
+            }
+        }
+    }
+
+    /// Every edge in the relation, grouped by `A`.
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = (Id<A>, Id<B>, &T)> {
+        self.forward
+            .iter()
+            .flatten()
+            .map(|(a, b, value)| (*a, *b, value))
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    #[inline]
+    fn get_by_id(&self, a: Id<A>, b: Id<B>) -> Option<&T> {
+        self.forward
+            .get(a.untyped.index())?
+            .iter()
+            .find(|(source, target, _)| *source == a && *target == b)
+            .map(|(_, _, value)| value)
+    }
+
+    #[inline]
+    fn get_mut_by_id(&mut self, a: Id<A>, b: Id<B>) -> Option<&mut T> {
+        self.forward
+            .get_mut(a.untyped.index())?
+            .iter_mut()
+            .find(|(source, target, _)| *source == a && *target == b)
+            .map(|(_, _, value)| value)
+    }
+
+    #[inline]
+    fn edges_mut<E>(edges: &mut IndexVec<Vec<E>>, index: usize) -> &mut Vec<E> {
+        if edges.get(index).is_none() {
+            edges.insert(index, Vec::new());
+        }
+        &mut edges[index]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_util::valid;
+
+    #[derive(Debug)]
+    struct Faction;
+    #[derive(Debug)]
+    struct System;
+
+    fn relation() -> Relation<Faction, System, u32> {
+        let mut relation = Relation::new();
+        relation.insert(valid(0), valid(1), 1);
+        relation.insert(valid(0), valid(2), 2);
+        relation.insert(valid(1), valid(2), 3);
+        relation
+    }
+
+    #[test]
+    fn insert_replaces_existing_edge() {
+        let mut relation = relation();
+
+        assert_eq!(Some(1), relation.insert(valid(0), valid(1), 4));
+        assert_eq!(Some(&4), relation.get(valid(0), valid(1)));
+        assert_eq!(3, relation.len());
+    }
+
+    #[test]
+    fn targets_and_sources() {
+        let relation = relation();
+
+        let targets = relation.targets(valid(0)).collect::<Vec<_>>();
+        let sources = relation.sources(valid(2)).collect::<Vec<_>>();
+
+        assert_eq!(vec![(Id::first(1), &1), (Id::first(2), &2)], targets);
+        assert_eq!(vec![(Id::first(0), &2), (Id::first(1), &3)], sources);
+    }
+
+    #[test]
+    fn remove_edge() {
+        let mut relation = relation();
+
+        assert_eq!(Some(2), relation.remove(valid(0), valid(2)));
+        assert_eq!(None, relation.remove(valid(0), valid(2)));
+        assert_eq!(1, relation.sources(valid(2)).count());
+        assert_eq!(2, relation.len());
+    }
+
+    #[test]
+    fn remove_source_and_target() {
+        let mut relation = relation();
+
+        relation.remove_target(Id::first(2));
+
+        assert_eq!(1, relation.len());
+        assert_eq!(0, relation.targets(valid(1)).count());
+
+        relation.remove_source(Id::first(0));
+
+        assert!(relation.is_empty());
+        assert_eq!(0, relation.sources(valid(1)).count());
+    }
+
+    #[test]
+    fn iter_yields_every_edge() {
+        let relation = relation();
+
+        let edges = relation.iter().collect::<Vec<_>>();
+
+        assert_eq!(
+            vec![
+                (Id::first(0), Id::first(1), &1),
+                (Id::first(0), Id::first(2), &2),
+                (Id::first(1), Id::first(2), &3),
+            ],
+            edges
+        );
+    }
+}
@@ -0,0 +1,366 @@
+use crate::component::Component;
+use crate::patch::Patch;
+use crate::rollback::Rollback;
+use std::convert::{TryFrom, TryInto};
+use std::fmt::{Display, Formatter};
+
+/// Writes and reads single values of a replicated component.
+pub trait Codec<T> {
+    fn encode(&self, value: &T, out: &mut Vec<u8>);
+
+    fn decode(&self, input: &mut &[u8]) -> Result<T, DecodeError>;
+}
+
+/// Encodes numbers as their little-endian bytes.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct LittleEndian;
+
+macro_rules! impl_little_endian {
+    ($($ty:ty),*) => {
+        $(
+            impl Codec<$ty> for LittleEndian {
+                #[inline]
+                fn encode(&self, value: &$ty, out: &mut Vec<u8>) {
+                    out.extend_from_slice(&value.to_le_bytes());
+                }
+
+                #[inline]
+                fn decode(&self, input: &mut &[u8]) -> Result<$ty, DecodeError> {
+                    let bytes = take(input, std::mem::size_of::<$ty>())?;
+                    Ok(<$ty>::from_le_bytes(bytes.try_into().expect("length checked")))
+                }
+            }
+        )*
+    };
+}
+
+impl_little_endian!(u8, u16, u32, u64, i8, i16, i32, i64, f32, f64);
+
+/// Returned when a replication frame cannot be decoded.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DecodeError {
+    /// The frame ended before all of its values were read.
+    UnexpectedEnd,
+    /// The frame was encoded against a baseline tick the decoder no longer has.
+    MissingBaseline(u64),
+    /// A codec rejected a value.
+    InvalidValue,
+}
+
+impl Display for DecodeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DecodeError::UnexpectedEnd => write!(f, "unexpected end of frame"),
+            DecodeError::MissingBaseline(tick) => write!(f, "missing baseline for tick {}", tick),
+            DecodeError::InvalidValue => write!(f, "invalid value"),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+const NO_BASELINE: u64 = u64::MAX;
+
+#[inline]
+fn take<'a>(input: &mut &'a [u8], len: usize) -> Result<&'a [u8], DecodeError> {
+    if input.len() < len {
+        return Err(DecodeError::UnexpectedEnd);
+    }
+    let (bytes, rest) = input.split_at(len);
+    *input = rest;
+    Ok(bytes)
+}
+
+#[inline]
+fn read_u64(input: &mut &[u8]) -> Result<u64, DecodeError> {
+    LittleEndian.decode(input)
+}
+
+/// Encodes each tick of a component as the changes since the last state the client acknowledged.
+///
+/// A frame is the tick, the baseline tick, the new length, a bitmask of the changed ids and then
+/// the value of every changed id. Until a tick is acknowledged, frames contain every value.
+#[derive(Debug)]
+pub struct Encoder<Arena, T, C> {
+    sent: Rollback<Component<Arena, T>>,
+    acked: Option<u64>,
+    codec: C,
+}
+
+impl<Arena, T: Clone + PartialEq, C: Codec<T>> Encoder<Arena, T, C> {
+    /// `history` is the number of sent states kept as possible baselines.
+    pub fn new(codec: C, history: usize) -> Self {
+        Self {
+            sent: Rollback::with_capacity(history),
+            acked: None,
+            codec,
+        }
+    }
+
+    /// Records that the client has reconstructed `tick`, making it available as a baseline.
+    #[inline]
+    pub fn ack(&mut self, tick: u64) {
+        if self.acked.is_none_or(|acked| tick > acked) {
+            self.acked = Some(tick);
+        }
+    }
+
+    #[inline]
+    pub fn acked(&self) -> Option<u64> {
+        self.acked
+    }
+
+    pub fn encode(&mut self, tick: u64, current: &Component<Arena, T>) -> Vec<u8> {
+        let empty = Component::default();
+        let (baseline_tick, baseline) = match self.acked.and_then(|t| Some((t, self.sent.get(t)?)))
+        {
+            Some((t, baseline)) => (t, baseline),
+            None => (NO_BASELINE, &empty),
+        };
+
+        let patch = Component::diff(baseline, current);
+
+        let mut out = Vec::new();
+        out.extend_from_slice(&tick.to_le_bytes());
+        out.extend_from_slice(&baseline_tick.to_le_bytes());
+        out.extend_from_slice(&(patch.to_len() as u64).to_le_bytes());
+
+        let mut mask = vec![0u64; patch.to_len().div_ceil(64)];
+        patch
+            .indices()
+            .for_each(|index| mask[index / 64] |= 1 << (index % 64));
+        mask.iter()
+            .for_each(|word| out.extend_from_slice(&word.to_le_bytes()));
+
+        patch
+            .changes()
+            .for_each(|(_, value)| self.codec.encode(value, &mut out));
+
+        self.sent.save(tick, current);
+        out
+    }
+}
+
+/// Rebuilds a component from the frames written by an [`Encoder`].
+#[derive(Debug)]
+pub struct Decoder<Arena, T, C> {
+    received: Rollback<Component<Arena, T>>,
+    codec: C,
+}
+
+impl<Arena, T: Clone, C: Codec<T>> Decoder<Arena, T, C> {
+    /// `history` is the number of received states kept as possible baselines.
+    pub fn new(codec: C, history: usize) -> Self {
+        Self {
+            received: Rollback::with_capacity(history),
+            codec,
+        }
+    }
+
+    /// The most recently decoded state.
+    #[inline]
+    pub fn latest(&self) -> Option<&Component<Arena, T>> {
+        self.received.get(self.received.latest()?)
+    }
+
+    /// Decodes a frame into `component` and returns its tick, which should be acknowledged.
+    pub fn decode(
+        &mut self,
+        mut frame: &[u8],
+        component: &mut Component<Arena, T>,
+    ) -> Result<u64, DecodeError> {
+        let input = &mut frame;
+        let tick = read_u64(input)?;
+        let baseline_tick = read_u64(input)?;
+        let len = usize::try_from(read_u64(input)?).map_err(|_| DecodeError::InvalidValue)?;
+
+        let words = len.div_ceil(64);
+        if words.checked_mul(8).is_none_or(|bytes| bytes > input.len()) {
+            return Err(DecodeError::UnexpectedEnd);
+        }
+
+        let mut mask = Vec::with_capacity(words);
+        for _ in 0..words {
+            mask.push(read_u64(input)?);
+        }
+        let changed = |index: usize| mask[index / 64] & (1 << (index % 64)) != 0;
+
+        let baseline = if baseline_tick == NO_BASELINE {
+            None
+        } else {
+            let baseline = self
+                .received
+                .get(baseline_tick)
+                .ok_or(DecodeError::MissingBaseline(baseline_tick))?;
+            Some(baseline)
+        };
+
+        // Values past the end of the baseline have nothing to fall back on, so they must be sent.
+        let baseline_len = baseline.map_or(0, |baseline| baseline.len());
+        if !(baseline_len.min(len)..len).all(changed) {
+            return Err(DecodeError::InvalidValue);
+        }
+
+        let mut changes = Vec::new();
+        for index in (0..len).filter(|index| changed(*index)) {
+            changes.push((index, self.codec.decode(input)?));
+        }
+        if !input.is_empty() {
+            return Err(DecodeError::InvalidValue);
+        }
+
+        match baseline {
+            Some(baseline) => component.clone_from(baseline),
+            None => *component = Component::default(),
+        }
+
+        component.apply(&Patch::from_parts(component.len(), len, changes));
+        self.received.save(tick, component);
+        Ok(tick)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_util::values;
+    use std::sync::mpsc::channel;
+
+    #[test]
+    fn frames_shrink_after_ack() {
+        let mut encoder = Encoder::<(), u32, _>::new(LittleEndian, 4);
+        let state = Component::from(vec![0; 100]);
+
+        let full = encoder.encode(0, &state);
+        encoder.ack(0);
+        let delta = encoder.encode(1, &state);
+
+        assert!(delta.len() < full.len());
+    }
+
+    #[test]
+    fn replicates_over_lossy_channel() {
+        let (to_client, from_server) = channel::<Vec<u8>>();
+        let (to_server, from_client) = channel::<u64>();
+
+        let mut encoder = Encoder::<(), u32, _>::new(LittleEndian, 8);
+        let mut decoder = Decoder::<(), u32, _>::new(LittleEndian, 8);
+        let mut server = Component::from(vec![0, 0, 0]);
+        let mut client = Component::default();
+
+        for tick in 0..11u64 {
+            server
+                .iter_mut()
+                .into_iter()
+                .enumerate()
+                .for_each(|(i, v)| {
+                    if tick as usize % 3 == i {
+                        *v += 1;
+                    }
+                });
+            if tick == 5 {
+                server.resize_with(5, || 7);
+            }
+
+            let frame = encoder.encode(tick, &server);
+            if tick % 4 != 1 {
+                to_client.send(frame).unwrap();
+            }
+
+            for frame in from_server.try_iter() {
+                let tick = decoder.decode(&frame, &mut client).unwrap();
+                to_server.send(tick).unwrap();
+            }
+
+            for tick in from_client.try_iter() {
+                encoder.ack(tick);
+            }
+
+            if tick % 4 != 1 {
+                assert_eq!(values(&server), values(&client));
+            }
+        }
+
+        assert_eq!(Some(10), encoder.acked());
+        assert_eq!(values(&server), values(decoder.latest().unwrap()));
+    }
+
+    #[test]
+    fn missing_baseline_is_an_error() {
+        let mut encoder = Encoder::<(), u32, _>::new(LittleEndian, 4);
+        let mut decoder = Decoder::<(), u32, _>::new(LittleEndian, 4);
+        let state = Component::from(vec![1]);
+
+        encoder.encode(0, &state);
+        encoder.ack(0);
+        let frame = encoder.encode(1, &state);
+
+        assert_eq!(
+            Err(DecodeError::MissingBaseline(0)),
+            decoder.decode(&frame, &mut Component::default())
+        );
+    }
+
+    fn frame(words: &[u64], values: &[u32]) -> Vec<u8> {
+        let mut frame = vec![];
+        words
+            .iter()
+            .for_each(|word| LittleEndian.encode(word, &mut frame));
+        values
+            .iter()
+            .for_each(|value| LittleEndian.encode(value, &mut frame));
+        frame
+    }
+
+    #[test]
+    fn truncated_frames_are_errors() {
+        let mut encoder = Encoder::<(), u32, _>::new(LittleEndian, 4);
+        let mut decoder = Decoder::<(), u32, _>::new(LittleEndian, 4);
+        let mut component = Component::default();
+
+        let full = encoder.encode(0, &Component::from(vec![1; 100]));
+        let forged = frame(&[0, NO_BASELINE, u64::MAX], &[]);
+
+        assert_eq!(
+            Err(DecodeError::UnexpectedEnd),
+            decoder.decode(&full[..full.len() - 1], &mut component)
+        );
+        assert_eq!(
+            Err(DecodeError::UnexpectedEnd),
+            decoder.decode(&full[..30], &mut component)
+        );
+        assert_eq!(
+            Err(DecodeError::UnexpectedEnd),
+            decoder.decode(&forged, &mut component)
+        );
+    }
+
+    #[test]
+    fn trailing_bytes_are_errors() {
+        let mut encoder = Encoder::<(), u32, _>::new(LittleEndian, 4);
+        let mut decoder = Decoder::<(), u32, _>::new(LittleEndian, 4);
+        let mut frame = encoder.encode(0, &Component::from(vec![1, 2]));
+        frame.push(0);
+
+        assert_eq!(
+            Err(DecodeError::InvalidValue),
+            decoder.decode(&frame, &mut Component::default())
+        );
+    }
+
+    #[test]
+    fn appended_values_must_be_in_mask() {
+        let mut decoder = Decoder::<(), u32, _>::new(LittleEndian, 4);
+        let mut component = Component::default();
+
+        let missing = frame(&[0, NO_BASELINE, 3, 0b011], &[1, 2]);
+        let complete = frame(&[0, NO_BASELINE, 3, 0b111], &[1, 2, 3]);
+
+        assert_eq!(
+            Err(DecodeError::InvalidValue),
+            decoder.decode(&missing, &mut component)
+        );
+        assert_eq!(Ok(0), decoder.decode(&complete, &mut component));
+        assert_eq!(vec![1, 2, 3], values(&component));
+    }
+}
@@ -0,0 +1,162 @@
+#[derive(Debug, Clone)]
+struct Slot<S> {
+    tick: Option<u64>,
+    state: S,
+}
+
+/// Keeps the last `capacity` snapshots of a state, such as a component or a struct of components.
+///
+/// Snapshots are written with `clone_from`, so once every slot has been filled, saving and
+/// restoring reuse the existing allocations. Discarded slots keep their storage for later saves.
+#[derive(Debug, Clone)]
+pub struct Rollback<S> {
+    slots: Vec<Slot<S>>,
+    capacity: usize,
+}
+
+impl<S: Clone> Rollback<S> {
+    pub fn with_capacity(capacity: usize) -> Self {
+        assert!(capacity > 0, "Rollback capacity must be non-zero");
+
+        Self {
+            slots: Vec::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// The number of saved snapshots.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.slots.iter().filter(|s| s.tick.is_some()).count()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Saves a snapshot for `tick`, replacing an existing snapshot of the same tick or, when full,
+    /// the oldest snapshot.
+    pub fn save(&mut self, tick: u64, state: &S) {
+        if let Some(slot) = self.slot_mut(tick) {
+            slot.state.clone_from(state);
+            return;
+        }
+
+        if let Some(slot) = self.slots.iter_mut().find(|s| s.tick.is_none()) {
+            slot.tick = Some(tick);
+            slot.state.clone_from(state);
+            return;
+        }
+
+        if self.slots.len() < self.capacity {
+            self.slots.push(Slot {
+                tick: Some(tick),
+                state: state.clone(),
+            });
+            return;
+        }
+
+        if let Some(slot) = self.slots.iter_mut().min_by_key(|s| s.tick) {
+            slot.tick = Some(tick);
+            slot.state.clone_from(state);
+        }
+    }
+
+    /// Copies the snapshot for `tick` into `state`, returning `false` if there is no such snapshot.
+    pub fn restore(&self, tick: u64, state: &mut S) -> bool {
+        match self.get(tick) {
+            Some(snapshot) => {
+                state.clone_from(snapshot);
+                true
+            }
+            None => false,
+        }
+    }
+
+    #[inline]
+    pub fn get(&self, tick: u64) -> Option<&S> {
+        self.slots
+            .iter()
+            .find(|s| s.tick == Some(tick))
+            .map(|s| &s.state)
+    }
+
+    /// Drops every snapshot newer than `tick`, e.g. after rolling back to it.
+    #[inline]
+    pub fn discard_after(&mut self, tick: u64) {
+        self.slots
+            .iter_mut()
+            .filter(|s| s.tick.is_some_and(|t| t > tick))
+            .for_each(|s| s.tick = None);
+    }
+
+    #[inline]
+    pub fn clear(&mut self) {
+        self.slots.iter_mut().for_each(|s| s.tick = None);
+    }
+
+    #[inline]
+    pub fn oldest(&self) -> Option<u64> {
+        self.slots.iter().filter_map(|s| s.tick).min()
+    }
+
+    #[inline]
+    pub fn latest(&self) -> Option<u64> {
+        self.slots.iter().filter_map(|s| s.tick).max()
+    }
+
+    /// The saved ticks, oldest first.
+    pub fn ticks(&self) -> Vec<u64> {
+        let mut ticks = self.slots.iter().filter_map(|s| s.tick).collect::<Vec<_>>();
+        ticks.sort_unstable();
+        ticks
+    }
+
+    #[inline]
+    fn slot_mut(&mut self, tick: u64) -> Option<&mut Slot<S>> {
+        self.slots.iter_mut().find(|s| s.tick == Some(tick))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_util::values;
+    use crate::Component;
+
+    #[test]
+    fn keeps_last_n_snapshots() {
+        let mut rollback = Rollback::with_capacity(2);
+        let mut state = Component::<(), u32>::from(vec![0]);
+
+        for tick in 0..3 {
+            state.iter_mut().into_iter().for_each(|v| *v = tick as u32);
+            rollback.save(tick, &state);
+        }
+
+        assert_eq!(vec![1, 2], rollback.ticks());
+        assert!(rollback.restore(1, &mut state));
+        assert_eq!(vec![1], values(&state));
+        assert!(!rollback.restore(0, &mut state));
+    }
+
+    #[test]
+    fn discard_after_frees_slots() {
+        let mut rollback = Rollback::with_capacity(3);
+        let state = Component::<(), u32>::from(vec![0]);
+        (0..3).for_each(|tick| rollback.save(tick, &state));
+
+        rollback.discard_after(0);
+        rollback.save(5, &state);
+
+        assert_eq!(vec![0, 5], rollback.ticks());
+        assert_eq!(Some(5), rollback.latest());
+        assert_eq!(Some(0), rollback.oldest());
+    }
+}
@@ -0,0 +1,650 @@
+use crate::component::Component;
+use crate::storage::Storage;
+use bytemuck::Pod;
+use std::convert::TryFrom;
+use std::ffi::CString;
+use std::io::{Error, ErrorKind, Result};
+use std::marker::PhantomData;
+use std::ops::{Index, IndexMut};
+use std::ptr::NonNull;
+use std::sync::atomic::{fence, AtomicU64, Ordering};
+
+const MAGIC: u64 = u64::from_le_bytes(*b"GIDCSHM1");
+const HEADER_LEN: usize = 64;
+
+#[repr(C)]
+struct Header {
+    magic: u64,
+    element_size: u64,
+    element_align: u64,
+    capacity: u64,
+    sequence: AtomicU64,
+    len: AtomicU64,
+}
+
+/// A mapping of a POSIX shared memory segment, which is unlinked on drop if this side created it.
+struct Segment {
+    ptr: NonNull<u8>,
+    size: usize,
+    owned: Option<CString>,
+}
+
+impl Segment {
+    fn map(fd: libc::c_int, size: usize, writable: bool) -> Result<Self> {
+        let protection = if writable {
+            libc::PROT_READ | libc::PROT_WRITE
+        } else {
+            libc::PROT_READ
+        };
+
+        let ptr = unsafe {
+            libc::mmap(
+                std::ptr::null_mut(),
+                size,
+                protection,
+                libc::MAP_SHARED,
+                fd,
+                0,
+            )
+        };
+        unsafe { libc::close(fd) };
+
+        if ptr == libc::MAP_FAILED {
+            return Err(Error::last_os_error());
+        }
+
+        Ok(Self {
+            ptr: NonNull::new(ptr as *mut u8).expect("mmap returned null"),
+            size,
+            owned: None,
+        })
+    }
+
+    #[inline]
+    fn header(&self) -> &Header {
+        unsafe { &*(self.ptr.as_ptr() as *const Header) }
+    }
+
+    #[inline]
+    fn data<T>(&self) -> *mut T {
+        unsafe { self.ptr.as_ptr().add(HEADER_LEN) as *mut T }
+    }
+}
+
+impl Drop for Segment {
+    fn drop(&mut self) {
+        unsafe { libc::munmap(self.ptr.as_ptr() as *mut libc::c_void, self.size) };
+        if let Some(name) = &self.owned {
+            unsafe { libc::shm_unlink(name.as_ptr()) };
+        }
+    }
+}
+
+fn segment_name(name: &str) -> Result<CString> {
+    CString::new(name).map_err(|_| Error::new(ErrorKind::InvalidInput, "name contains a nul byte"))
+}
+
+fn check_layout<T>() -> Result<()> {
+    if std::mem::align_of::<T>() > HEADER_LEN || std::mem::size_of::<T>() == 0 {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "unsupported element layout",
+        ));
+    }
+    Ok(())
+}
+
+/// A fixed-capacity [`Storage`] that keeps its values in a POSIX shared memory segment, so that
+/// other processes can observe them through a [`SharedReader`].
+///
+/// The segment starts with a 64-byte header holding the element size, the capacity, the current
+/// length and a sequence counter, followed by the values themselves. Writes go straight into the
+/// segment. The counter turns odd on the first write after a [`publish`](Self::publish) and even
+/// again on the next one, which lets readers retry instead of taking a lock.
+///
+/// A default `SharedVec` has no segment and a capacity of zero. The segment is unlinked when the
+/// `SharedVec` that created it is dropped.
+pub struct SharedVec<T> {
+    segment: Option<Segment>,
+    marker: PhantomData<*const T>,
+}
+
+impl<T> Default for SharedVec<T> {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            segment: None,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T> std::fmt::Debug for SharedVec<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SharedVec")
+            .field(
+                "name",
+                &self.segment.as_ref().and_then(|s| s.owned.as_ref()),
+            )
+            .field("capacity", &self.capacity())
+            .field("len", &self.len())
+            .finish()
+    }
+}
+
+impl<T> SharedVec<T> {
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.segment
+            .as_ref()
+            .map_or(0, |segment| segment.header().capacity as usize)
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.segment.as_ref().map_or(0, |segment| {
+            segment.header().len.load(Ordering::Relaxed) as usize
+        })
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The number of publishes, times two, plus one while there are unpublished writes.
+    #[inline]
+    pub fn sequence(&self) -> u64 {
+        self.segment.as_ref().map_or(0, |segment| {
+            segment.header().sequence.load(Ordering::Acquire)
+        })
+    }
+
+    /// Makes the writes since the last publish visible to readers.
+    #[inline]
+    pub fn publish(&mut self) {
+        if let Some(segment) = &self.segment {
+            let sequence = segment.header().sequence.load(Ordering::Relaxed);
+            if sequence % 2 == 1 {
+                segment
+                    .header()
+                    .sequence
+                    .store(sequence + 1, Ordering::Release);
+            }
+        }
+    }
+
+    #[inline]
+    pub fn as_slice(&self) -> &[T] {
+        match &self.segment {
+            Some(segment) => unsafe { std::slice::from_raw_parts(segment.data(), self.len()) },
+            None => &[],
+        }
+    }
+
+    #[inline]
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        self.begin_write();
+        match &self.segment {
+            Some(segment) => unsafe { std::slice::from_raw_parts_mut(segment.data(), self.len()) },
+            None => &mut [],
+        }
+    }
+
+    /// Marks the segment as mid-write until the next publish, so readers stop trusting it.
+    #[inline]
+    fn begin_write(&self) {
+        if let Some(segment) = &self.segment {
+            let sequence = segment.header().sequence.load(Ordering::Relaxed);
+            if sequence % 2 == 0 {
+                segment
+                    .header()
+                    .sequence
+                    .store(sequence + 1, Ordering::Relaxed);
+                fence(Ordering::Release);
+            }
+        }
+    }
+
+    /// Extends the values with `f` up to `len`, which the caller has checked against the capacity.
+    fn extend_with<F: FnMut() -> T>(&mut self, len: usize, mut f: F) {
+        self.begin_write();
+        if let Some(segment) = &self.segment {
+            let data = segment.data::<T>();
+            for index in self.len()..len {
+                unsafe { std::ptr::write(data.add(index), f()) };
+            }
+            segment.header().len.store(len as u64, Ordering::Relaxed);
+        }
+    }
+
+    #[inline]
+    fn check_capacity(&self, len: usize) {
+        assert!(len <= self.capacity(), "Component exceeds shared capacity");
+    }
+}
+
+impl<T: Pod> SharedVec<T> {
+    /// Creates the segment `name`, which must start with `/`, with room for `capacity` values.
+    pub fn create(name: &str, capacity: usize) -> Result<Self> {
+        check_layout::<T>()?;
+
+        let size = capacity
+            .checked_mul(std::mem::size_of::<T>())
+            .and_then(|bytes| bytes.checked_add(HEADER_LEN))
+            .and_then(|size| libc::off_t::try_from(size).ok().map(|_| size))
+            .ok_or_else(|| Error::new(ErrorKind::InvalidInput, "capacity is too large"))?;
+        let name = segment_name(name)?;
+
+        let fd = unsafe {
+            libc::shm_open(
+                name.as_ptr(),
+                libc::O_CREAT | libc::O_EXCL | libc::O_RDWR,
+                0o600,
+            )
+        };
+        if fd < 0 {
+            return Err(Error::last_os_error());
+        }
+
+        if unsafe { libc::ftruncate(fd, size as libc::off_t) } != 0 {
+            let error = Error::last_os_error();
+            unsafe {
+                libc::close(fd);
+                libc::shm_unlink(name.as_ptr());
+            }
+            return Err(error);
+        }
+
+        let mut segment = match Segment::map(fd, size, true) {
+            Ok(segment) => segment,
+            Err(error) => {
+                unsafe { libc::shm_unlink(name.as_ptr()) };
+                return Err(error);
+            }
+        };
+        segment.owned = Some(name);
+
+        unsafe {
+            std::ptr::write(
+                segment.ptr.as_ptr() as *mut Header,
+                Header {
+                    magic: MAGIC,
+                    element_size: std::mem::size_of::<T>() as u64,
+                    element_align: std::mem::align_of::<T>() as u64,
+                    capacity: capacity as u64,
+                    sequence: AtomicU64::new(0),
+                    len: AtomicU64::new(0),
+                },
+            );
+        }
+
+        Ok(Self {
+            segment: Some(segment),
+            marker: PhantomData,
+        })
+    }
+}
+
+impl<T> Index<usize> for SharedVec<T> {
+    type Output = T;
+
+    #[inline]
+    fn index(&self, index: usize) -> &Self::Output {
+        self.as_slice().index(index)
+    }
+}
+
+impl<T> IndexMut<usize> for SharedVec<T> {
+    #[inline]
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        self.as_mut_slice().index_mut(index)
+    }
+}
+
+impl<T: Pod> Storage<T> for SharedVec<T> {
+    type Iter<'a>
+        = std::slice::Iter<'a, T>
+    where
+        T: 'a;
+
+    type IterMut<'a>
+        = std::slice::IterMut<'a, T>
+    where
+        T: 'a;
+
+    /// # Panics
+    ///
+    /// Panics if `index` is past the capacity of the segment.
+    #[inline]
+    fn insert_with<F: Fn() -> T>(&mut self, index: usize, value: T, fill: F) {
+        if index < self.len() {
+            self.as_mut_slice()[index] = value;
+        } else {
+            self.check_capacity(index + 1);
+            self.extend_with(index, fill);
+            let mut value = Some(value);
+            self.extend_with(index + 1, || value.take().expect("one value"));
+        }
+    }
+
+    #[inline]
+    fn get(&self, index: usize) -> Option<&T> {
+        self.as_slice().get(index)
+    }
+
+    #[inline]
+    fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        self.as_mut_slice().get_mut(index)
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        SharedVec::len(self)
+    }
+
+    #[inline]
+    fn swap(&mut self, a: usize, b: usize) {
+        self.as_mut_slice().swap(a, b);
+    }
+
+    /// # Panics
+    ///
+    /// Panics if `len` is past the capacity of the segment.
+    #[inline]
+    fn resize_with<F: FnMut() -> T>(&mut self, len: usize, f: F) {
+        self.check_capacity(len);
+        if len <= self.len() {
+            self.begin_write();
+            if let Some(segment) = &self.segment {
+                segment.header().len.store(len as u64, Ordering::Relaxed);
+            }
+        } else {
+            self.extend_with(len, f);
+        }
+    }
+
+    #[inline]
+    fn fill_with<F: FnMut() -> T>(&mut self, f: F) {
+        self.as_mut_slice().fill_with(f);
+    }
+
+    #[inline]
+    fn iter(&self) -> Self::Iter<'_> {
+        self.as_slice().iter()
+    }
+
+    #[inline]
+    fn iter_mut(&mut self) -> Self::IterMut<'_> {
+        self.as_mut_slice().iter_mut()
+    }
+}
+
+/// A `Component` whose values live in a POSIX shared memory segment.
+pub type SharedComponent<Arena, T> = Component<Arena, T, SharedVec<T>>;
+
+impl<Arena, T: Pod> SharedComponent<Arena, T> {
+    /// Creates a component in the segment `name` with room for `capacity` values.
+    #[inline]
+    pub fn create(name: &str, capacity: usize) -> Result<Self> {
+        SharedVec::create(name, capacity).map(Self::from)
+    }
+
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.raw().storage().capacity()
+    }
+
+    #[inline]
+    pub fn sequence(&self) -> u64 {
+        self.raw().storage().sequence()
+    }
+
+    /// Makes the writes since the last publish visible to readers.
+    #[inline]
+    pub fn publish(&mut self) {
+        self.raw_mut().storage_mut().publish();
+    }
+
+    #[inline]
+    pub fn as_slice(&self) -> &[T] {
+        self.raw().storage().as_slice()
+    }
+
+    #[inline]
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        self.raw_mut().storage_mut().as_mut_slice()
+    }
+}
+
+/// Observes a [`SharedComponent`] from another process.
+pub struct SharedReader<Arena, T> {
+    segment: Segment,
+    marker: PhantomData<*const (Arena, T)>,
+}
+
+impl<Arena, T> std::fmt::Debug for SharedReader<Arena, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SharedReader")
+            .field("capacity", &self.segment.header().capacity)
+            .finish()
+    }
+}
+
+impl<Arena, T: Pod> SharedReader<Arena, T> {
+    /// Maps the segment `name` read-only, checking that its header matches `T`.
+    pub fn open(name: &str) -> Result<Self> {
+        check_layout::<T>()?;
+
+        let name = segment_name(name)?;
+        let fd = unsafe { libc::shm_open(name.as_ptr(), libc::O_RDONLY, 0) };
+        if fd < 0 {
+            return Err(Error::last_os_error());
+        }
+
+        let mut stat = unsafe { std::mem::zeroed::<libc::stat>() };
+        if unsafe { libc::fstat(fd, &mut stat) } != 0 {
+            let error = Error::last_os_error();
+            unsafe { libc::close(fd) };
+            return Err(error);
+        }
+
+        let size = stat.st_size as usize;
+        if size < HEADER_LEN {
+            unsafe { libc::close(fd) };
+            return Err(Error::new(ErrorKind::InvalidData, "segment is too small"));
+        }
+
+        let segment = Segment::map(fd, size, false)?;
+        let header = segment.header();
+
+        if header.magic != MAGIC {
+            return Err(Error::new(ErrorKind::InvalidData, "not a shared component"));
+        }
+
+        if header.element_size != std::mem::size_of::<T>() as u64
+            || header.element_align != std::mem::align_of::<T>() as u64
+        {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "element layout does not match",
+            ));
+        }
+
+        let required = usize::try_from(header.capacity)
+            .ok()
+            .and_then(|capacity| capacity.checked_mul(std::mem::size_of::<T>()))
+            .and_then(|bytes| bytes.checked_add(HEADER_LEN));
+        if required.is_none_or(|required| size < required) {
+            return Err(Error::new(ErrorKind::InvalidData, "segment is too small"));
+        }
+
+        Ok(Self {
+            segment,
+            marker: PhantomData,
+        })
+    }
+
+    /// Copies the last published state into `component`, retrying while there are unpublished
+    /// writes. Returns the sequence number of the snapshot.
+    pub fn read_into(&self, component: &mut Component<Arena, T>) -> u64 {
+        loop {
+            if let Some(sequence) = self.try_read_into(component) {
+                return sequence;
+            }
+            std::hint::spin_loop();
+        }
+    }
+
+    /// Makes a single attempt at [`read_into`](Self::read_into), returning `None` if the writer
+    /// had unpublished writes.
+    pub fn try_read_into(&self, component: &mut Component<Arena, T>) -> Option<u64> {
+        let header = self.segment.header();
+
+        let before = header.sequence.load(Ordering::Acquire);
+        if before % 2 == 1 {
+            return None;
+        }
+
+        let len = (header.len.load(Ordering::Relaxed) as usize).min(header.capacity as usize);
+        component.resize_with(len, T::zeroed);
+        let data = self.segment.data::<T>();
+        for (index, value) in component.as_mut_slice().iter_mut().enumerate() {
+            *value = unsafe { std::ptr::read_volatile(data.add(index)) };
+        }
+
+        fence(Ordering::Acquire);
+        let after = header.sequence.load(Ordering::Relaxed);
+        if before == after {
+            Some(before)
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    pub fn sequence(&self) -> u64 {
+        self.segment.header().sequence.load(Ordering::Acquire)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_util::valid;
+
+    fn name(test: &str) -> String {
+        format!("/gen_id_component_{}_{}", std::process::id(), test)
+    }
+
+    #[test]
+    fn reader_observes_published_writes() {
+        let name = name("observe");
+        let mut component = SharedComponent::<(), f32>::create(&name, 4).unwrap();
+        let reader = SharedReader::<(), f32>::open(&name).unwrap();
+        let mut observed = Component::default();
+
+        assert_eq!(0, reader.read_into(&mut observed));
+        assert!(observed.is_empty());
+
+        component.insert(valid(0), 1.0);
+        component.insert(valid(1), 3.0);
+
+        assert_eq!(None, reader.try_read_into(&mut observed));
+
+        component.publish();
+
+        assert_eq!(2, reader.read_into(&mut observed));
+        assert_eq!(&[1.0, 3.0], observed.as_slice());
+    }
+
+    #[test]
+    fn component_operators_write_into_segment() {
+        let name = name("operators");
+        let mut component = SharedComponent::<(), f32>::create(&name, 3).unwrap();
+        let reader = SharedReader::<(), f32>::open(&name).unwrap();
+        let mut observed = Component::default();
+        let ones = Component::<(), f32>::from(vec![1.0; 3]);
+
+        component.resize_with(3, || 1.0);
+        component += &ones;
+        component[valid(1)] = 5.0;
+        component.publish();
+        reader.read_into(&mut observed);
+
+        assert_eq!(&[2.0, 5.0, 2.0], component.as_slice());
+        assert_eq!(component.as_slice(), observed.as_slice());
+    }
+
+    #[test]
+    fn publish_without_writes_keeps_sequence() {
+        let name = name("sequence");
+        let mut component = SharedComponent::<(), u8>::create(&name, 1).unwrap();
+
+        component.publish();
+        assert_eq!(0, component.sequence());
+
+        component.insert(valid(0), 1);
+        assert_eq!(1, component.sequence());
+
+        component.publish();
+        assert_eq!(2, component.sequence());
+    }
+
+    #[test]
+    fn open_checks_element_layout() {
+        let name = name("layout");
+        let _component = SharedComponent::<(), u32>::create(&name, 1).unwrap();
+
+        let error = SharedReader::<(), u64>::open(&name).unwrap_err();
+
+        assert_eq!(ErrorKind::InvalidData, error.kind());
+    }
+
+    #[test]
+    fn segment_is_unlinked_on_drop() {
+        let name = name("unlink");
+        drop(SharedComponent::<(), u8>::create(&name, 1).unwrap());
+
+        assert!(SharedReader::<(), u8>::open(&name).is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "Component exceeds shared capacity")]
+    fn insert_checks_capacity() {
+        let name = name("capacity");
+        let mut component = SharedComponent::<(), u8>::create(&name, 1).unwrap();
+
+        component.insert(valid(1), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "Component exceeds shared capacity")]
+    fn default_has_no_capacity() {
+        let mut component = SharedComponent::<(), u8>::default();
+
+        component.insert(valid(0), 1);
+    }
+
+    #[test]
+    fn create_rejects_overflowing_capacity() {
+        let name = name("overflow");
+
+        let error = SharedVec::<u64>::create(&name, usize::MAX).unwrap_err();
+
+        assert_eq!(ErrorKind::InvalidInput, error.kind());
+        assert!(SharedReader::<(), u64>::open(&name).is_err());
+    }
+
+    #[test]
+    fn open_rejects_overflowing_capacity() {
+        let name = name("corrupt");
+        let values = SharedVec::<u64>::create(&name, 1).unwrap();
+        let segment = values.segment.as_ref().unwrap();
+        unsafe { (*(segment.ptr.as_ptr() as *mut Header)).capacity = u64::MAX };
+
+        let error = SharedReader::<(), u64>::open(&name).unwrap_err();
+
+        assert_eq!(ErrorKind::InvalidData, error.kind());
+    }
+}
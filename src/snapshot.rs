@@ -0,0 +1,413 @@
+use crate::component::Component;
+use crate::replication::{Codec, DecodeError, LittleEndian};
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+
+const MAGIC: &[u8; 4] = b"GIDC";
+const FORMAT_VERSION: u32 = 1;
+
+/// Returned when a snapshot cannot be read or one of its components cannot be loaded.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SnapshotError {
+    /// The bytes do not start with the snapshot magic number.
+    NotASnapshot,
+    /// The snapshot was written by a newer, unknown format.
+    UnsupportedFormat(u32),
+    /// The component was saved with a version that is neither current nor has a migration.
+    UnknownVersion {
+        name: String,
+        version: u32,
+    },
+    /// The component's values did not use exactly the bytes recorded in the header.
+    LengthMismatch {
+        name: String,
+    },
+    Decode(DecodeError),
+}
+
+impl Display for SnapshotError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SnapshotError::NotASnapshot => write!(f, "not a snapshot"),
+            SnapshotError::UnsupportedFormat(format) => {
+                write!(f, "unsupported snapshot format {}", format)
+            }
+            SnapshotError::UnknownVersion { name, version } => write!(
+                f,
+                "component `{}` has version {} with no migration",
+                name, version
+            ),
+            SnapshotError::LengthMismatch { name } => {
+                write!(f, "component `{}` does not match its recorded length", name)
+            }
+            SnapshotError::Decode(error) => error.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for SnapshotError {}
+
+impl From<DecodeError> for SnapshotError {
+    #[inline]
+    fn from(error: DecodeError) -> Self {
+        SnapshotError::Decode(error)
+    }
+}
+
+/// The header entry of one component in a snapshot.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SnapshotEntry {
+    pub name: String,
+    pub type_name: String,
+    pub version: u32,
+    pub len: usize,
+}
+
+#[derive(Debug, Clone)]
+struct Block {
+    entry: SnapshotEntry,
+    bytes: Vec<u8>,
+}
+
+type Migration<T> = Box<dyn Fn(&mut &[u8]) -> Result<T, DecodeError>>;
+
+/// Converts values saved with older versions of an element type into the current type.
+pub struct Migrations<T> {
+    hooks: HashMap<u32, Migration<T>>,
+}
+
+impl<T> Default for Migrations<T> {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            hooks: HashMap::new(),
+        }
+    }
+}
+
+impl<T> std::fmt::Debug for Migrations<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_set().entries(self.hooks.keys()).finish()
+    }
+}
+
+impl<T> Migrations<T> {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reads values saved as `version` with `codec` and converts them with `convert`.
+    pub fn add<Old, C, F>(mut self, version: u32, codec: C, convert: F) -> Self
+    where
+        C: Codec<Old> + 'static,
+        F: Fn(Old) -> T + 'static,
+    {
+        let hook = move |input: &mut &[u8]| codec.decode(input).map(&convert);
+        self.hooks.insert(version, Box::new(hook));
+        self
+    }
+}
+
+/// Writes named components into a snapshot along with their element type names and versions.
+#[derive(Debug, Default, Clone)]
+pub struct SnapshotWriter {
+    blocks: Vec<Block>,
+}
+
+impl SnapshotWriter {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn component<Arena, T, C: Codec<T>>(
+        &mut self,
+        name: &str,
+        version: u32,
+        component: &Component<Arena, T>,
+        codec: &C,
+    ) -> &mut Self {
+        let mut bytes = Vec::new();
+        component
+            .into_iter()
+            .for_each(|value| codec.encode(value, &mut bytes));
+
+        self.blocks.retain(|b| b.entry.name != name);
+        self.blocks.push(Block {
+            entry: SnapshotEntry {
+                name: name.to_string(),
+                type_name: std::any::type_name::<T>().to_string(),
+                version,
+                len: component.len(),
+            },
+            bytes,
+        });
+        self
+    }
+
+    pub fn finish(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        out.extend_from_slice(MAGIC);
+        LittleEndian.encode(&FORMAT_VERSION, &mut out);
+        LittleEndian.encode(&(self.blocks.len() as u32), &mut out);
+
+        for block in &self.blocks {
+            write_str(&block.entry.name, &mut out);
+            write_str(&block.entry.type_name, &mut out);
+            LittleEndian.encode(&block.entry.version, &mut out);
+            LittleEndian.encode(&(block.entry.len as u64), &mut out);
+            LittleEndian.encode(&(block.bytes.len() as u64), &mut out);
+        }
+
+        for block in &self.blocks {
+            out.extend_from_slice(&block.bytes);
+        }
+
+        out
+    }
+}
+
+/// A snapshot read back from bytes, from which components are loaded by name.
+///
+/// Components in the file that are never loaded are ignored, and loading a name that is not in
+/// the file returns `None`, so components can be added and removed between versions.
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    blocks: Vec<Block>,
+}
+
+impl Snapshot {
+    pub fn read(mut bytes: &[u8]) -> Result<Self, SnapshotError> {
+        let input = &mut bytes;
+
+        if input.len() < MAGIC.len() || &input[..MAGIC.len()] != MAGIC {
+            return Err(SnapshotError::NotASnapshot);
+        }
+        *input = &input[MAGIC.len()..];
+
+        let format: u32 = LittleEndian.decode(input)?;
+        if format > FORMAT_VERSION {
+            return Err(SnapshotError::UnsupportedFormat(format));
+        }
+
+        let count: u32 = LittleEndian.decode(input)?;
+        let mut entries = Vec::new();
+        for _ in 0..count {
+            let name = read_str(input)?;
+            let type_name = read_str(input)?;
+            let version: u32 = LittleEndian.decode(input)?;
+            let len: u64 = LittleEndian.decode(input)?;
+            let byte_len: u64 = LittleEndian.decode(input)?;
+            let entry = SnapshotEntry {
+                name,
+                type_name,
+                version,
+                len: len as usize,
+            };
+            entries.push((entry, byte_len as usize));
+        }
+
+        let mut blocks = Vec::with_capacity(entries.len());
+        for (entry, byte_len) in entries {
+            if input.len() < byte_len {
+                return Err(DecodeError::UnexpectedEnd.into());
+            }
+            let (block, rest) = input.split_at(byte_len);
+            *input = rest;
+            blocks.push(Block {
+                entry,
+                bytes: block.to_vec(),
+            });
+        }
+
+        Ok(Self { blocks })
+    }
+
+    #[inline]
+    pub fn entries(&self) -> impl Iterator<Item = &SnapshotEntry> {
+        self.blocks.iter().map(|b| &b.entry)
+    }
+
+    #[inline]
+    pub fn entry(&self, name: &str) -> Option<&SnapshotEntry> {
+        self.block(name).map(|b| &b.entry)
+    }
+
+    /// Loads a component saved with the current `version`.
+    #[inline]
+    pub fn load<Arena, T, C: Codec<T>>(
+        &self,
+        name: &str,
+        version: u32,
+        codec: &C,
+    ) -> Result<Option<Component<Arena, T>>, SnapshotError> {
+        self.load_with(name, version, codec, &Migrations::new())
+    }
+
+    /// Loads a component, using `migrations` if it was saved with an older version.
+    pub fn load_with<Arena, T, C: Codec<T>>(
+        &self,
+        name: &str,
+        version: u32,
+        codec: &C,
+        migrations: &Migrations<T>,
+    ) -> Result<Option<Component<Arena, T>>, SnapshotError> {
+        let block = match self.block(name) {
+            Some(block) => block,
+            None => return Ok(None),
+        };
+
+        let entry = &block.entry;
+        let mut input = block.bytes.as_slice();
+        // The length comes from the file, so it only bounds the allocation together with the
+        // number of bytes that are actually there.
+        let mut values = Vec::with_capacity(entry.len.min(block.bytes.len()));
+
+        if entry.version == version {
+            for _ in 0..entry.len {
+                values.push(codec.decode(&mut input)?);
+            }
+        } else {
+            let hook = migrations.hooks.get(&entry.version).ok_or_else(|| {
+                SnapshotError::UnknownVersion {
+                    name: entry.name.clone(),
+                    version: entry.version,
+                }
+            })?;
+            for _ in 0..entry.len {
+                values.push(hook(&mut input)?);
+            }
+        }
+
+        if !input.is_empty() {
+            return Err(SnapshotError::LengthMismatch {
+                name: entry.name.clone(),
+            });
+        }
+
+        Ok(Some(values.into()))
+    }
+
+    #[inline]
+    fn block(&self, name: &str) -> Option<&Block> {
+        self.blocks.iter().find(|b| b.entry.name == name)
+    }
+}
+
+#[inline]
+fn write_str(value: &str, out: &mut Vec<u8>) {
+    LittleEndian.encode(&(value.len() as u32), out);
+    out.extend_from_slice(value.as_bytes());
+}
+
+#[inline]
+fn read_str(input: &mut &[u8]) -> Result<String, DecodeError> {
+    let len: u32 = LittleEndian.decode(input)?;
+    let len = len as usize;
+    if input.len() < len {
+        return Err(DecodeError::UnexpectedEnd);
+    }
+    let (bytes, rest) = input.split_at(len);
+    *input = rest;
+    String::from_utf8(bytes.to_vec()).map_err(|_| DecodeError::InvalidValue)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_util::values;
+
+    #[test]
+    fn round_trip() {
+        let mass = Component::<(), f32>::from(vec![1.0, 2.0]);
+        let count = Component::<(), u8>::from(vec![3, 4]);
+        let bytes = SnapshotWriter::new()
+            .component("mass", 1, &mass, &LittleEndian)
+            .component("count", 1, &count, &LittleEndian)
+            .finish();
+
+        let snapshot = Snapshot::read(&bytes).unwrap();
+        let mass: Component<(), f32> = snapshot.load("mass", 1, &LittleEndian).unwrap().unwrap();
+        let count: Component<(), u8> = snapshot.load("count", 1, &LittleEndian).unwrap().unwrap();
+
+        assert_eq!(vec![1.0, 2.0], values(&mass));
+        assert_eq!(vec![3, 4], values(&count));
+        assert_eq!("f32", snapshot.entry("mass").unwrap().type_name);
+    }
+
+    #[test]
+    fn added_components_are_missing() {
+        let bytes = SnapshotWriter::new().finish();
+        let snapshot = Snapshot::read(&bytes).unwrap();
+
+        let loaded = snapshot.load::<(), u32, _>("new", 1, &LittleEndian);
+
+        assert_eq!(Ok(None), loaded.map(|c| c.map(|c| values(&c))));
+    }
+
+    #[test]
+    fn migrates_old_versions() {
+        let old = Component::<(), u16>::from(vec![1, 2]);
+        let bytes = SnapshotWriter::new()
+            .component("health", 1, &old, &LittleEndian)
+            .finish();
+        let snapshot = Snapshot::read(&bytes).unwrap();
+
+        let migrations = Migrations::new().add(1, LittleEndian, |old: u16| old as f64 * 0.5);
+        let health: Component<(), f64> = snapshot
+            .load_with("health", 2, &LittleEndian, &migrations)
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(vec![0.5, 1.0], values(&health));
+    }
+
+    #[test]
+    fn unknown_version_is_an_error() {
+        let old = Component::<(), u16>::from(vec![1]);
+        let bytes = SnapshotWriter::new()
+            .component("health", 1, &old, &LittleEndian)
+            .finish();
+        let snapshot = Snapshot::read(&bytes).unwrap();
+
+        let error = snapshot
+            .load::<(), f64, _>("health", 2, &LittleEndian)
+            .unwrap_err();
+
+        assert_eq!(
+            SnapshotError::UnknownVersion {
+                name: "health".to_string(),
+                version: 1
+            },
+            error
+        );
+    }
+
+    #[test]
+    fn rejects_other_bytes() {
+        assert_eq!(
+            SnapshotError::NotASnapshot,
+            Snapshot::read(b"nope").unwrap_err()
+        );
+    }
+
+    #[test]
+    fn corrupt_length_is_an_error() {
+        let mass = Component::<(), u32>::from(vec![1, 2]);
+        let mut bytes = SnapshotWriter::new()
+            .component("mass", 1, &mass, &LittleEndian)
+            .finish();
+
+        let header = [2u64.to_le_bytes(), 8u64.to_le_bytes()].concat();
+        let offset = bytes.windows(16).position(|w| w == header).unwrap();
+        bytes[offset..offset + 8].copy_from_slice(&u64::MAX.to_le_bytes());
+
+        let snapshot = Snapshot::read(&bytes).unwrap();
+
+        assert!(matches!(
+            snapshot.load::<(), u32, _>("mass", 1, &LittleEndian),
+            Err(SnapshotError::Decode(DecodeError::UnexpectedEnd))
+        ));
+    }
+}
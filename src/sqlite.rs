@@ -0,0 +1,339 @@
+use crate::arena::LengthMismatch;
+use crate::component::Component;
+use crate::raw_component::RawComponent;
+use rusqlite::types::{FromSql, ToSqlOutput};
+use rusqlite::{params_from_iter, Connection, ToSql};
+use std::fmt::{Display, Formatter};
+use std::path::Path;
+
+/// The column that holds the generation of each id, if it was saved.
+const GENERATION: &str = "generation";
+
+/// Returned when an arena cannot be saved to or loaded from SQLite.
+#[derive(Debug)]
+pub enum SqliteError {
+    Sqlite(rusqlite::Error),
+    /// The components saved as columns do not all have the same length.
+    LengthMismatch(LengthMismatch),
+    /// The table has no row whose id is this index, so the rows do not form a component.
+    MissingId(usize),
+}
+
+impl Display for SqliteError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SqliteError::Sqlite(error) => error.fmt(f),
+            SqliteError::LengthMismatch(error) => error.fmt(f),
+            SqliteError::MissingId(index) => write!(f, "missing row for id {}", index),
+        }
+    }
+}
+
+impl std::error::Error for SqliteError {}
+
+impl From<rusqlite::Error> for SqliteError {
+    #[inline]
+    fn from(error: rusqlite::Error) -> Self {
+        SqliteError::Sqlite(error)
+    }
+}
+
+impl From<LengthMismatch> for SqliteError {
+    #[inline]
+    fn from(error: LengthMismatch) -> Self {
+        SqliteError::LengthMismatch(error)
+    }
+}
+
+/// A component that can be saved as a column of an arena's table.
+pub trait SqlColumn<Arena> {
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn value(&self, index: usize) -> rusqlite::Result<ToSqlOutput<'_>>;
+}
+
+impl<Arena, T: ToSql> SqlColumn<Arena> for RawComponent<Arena, T> {
+    #[inline]
+    fn len(&self) -> usize {
+        self.len()
+    }
+
+    #[inline]
+    fn value(&self, index: usize) -> rusqlite::Result<ToSqlOutput<'_>> {
+        self.as_slice()[index].to_sql()
+    }
+}
+
+impl<Arena, T: ToSql> SqlColumn<Arena> for Component<Arena, T> {
+    #[inline]
+    fn len(&self) -> usize {
+        self.len()
+    }
+
+    #[inline]
+    fn value(&self, index: usize) -> rusqlite::Result<ToSqlOutput<'_>> {
+        self.as_slice()[index].to_sql()
+    }
+}
+
+/// A local SQLite file that stores each arena as a table, with an `id` primary key holding the id
+/// index, an optional `generation` column and one column per component.
+#[derive(Debug)]
+pub struct SqliteStore {
+    connection: Connection,
+}
+
+impl SqliteStore {
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, SqliteError> {
+        Ok(Self {
+            connection: Connection::open(path)?,
+        })
+    }
+
+    /// Replaces `table` with one row per id and one column per component.
+    ///
+    /// Only the id index is stored. Use [`save_with_generations`](Self::save_with_generations) to
+    /// keep the generations as well.
+    pub fn save<Arena>(
+        &mut self,
+        table: &str,
+        columns: &[(&'static str, &dyn SqlColumn<Arena>)],
+    ) -> Result<(), SqliteError> {
+        self.save_table(table, None, columns)
+    }
+
+    /// Like [`save`](Self::save), but also stores the generation of each id, such as those read
+    /// from the arena's allocator, in a `generation` column.
+    pub fn save_with_generations<Arena>(
+        &mut self,
+        table: &str,
+        generations: &[u32],
+        columns: &[(&'static str, &dyn SqlColumn<Arena>)],
+    ) -> Result<(), SqliteError> {
+        self.save_table(table, Some(generations), columns)
+    }
+
+    fn save_table<Arena>(
+        &mut self,
+        table: &str,
+        generations: Option<&[u32]>,
+        columns: &[(&'static str, &dyn SqlColumn<Arena>)],
+    ) -> Result<(), SqliteError> {
+        let expected = columns
+            .first()
+            .map(|(_, c)| c.len())
+            .or_else(|| generations.map(<[u32]>::len))
+            .unwrap_or(0);
+        let lengths = generations
+            .map(|generations| (GENERATION, generations.len()))
+            .into_iter()
+            .chain(
+                columns
+                    .iter()
+                    .map(|(column, values)| (*column, values.len())),
+            );
+        for (column, len) in lengths {
+            if len != expected {
+                return Err(LengthMismatch {
+                    column,
+                    len,
+                    expected,
+                }
+                .into());
+            }
+        }
+
+        let names = generations
+            .map(|_| GENERATION)
+            .into_iter()
+            .chain(columns.iter().map(|(name, _)| *name))
+            .map(|name| format!(", {}", quote(name)))
+            .collect::<String>();
+        let parameters = (0..columns.len() + generations.map_or(0, |_| 1))
+            .map(|i| format!(", ?{}", i + 2))
+            .collect::<String>();
+
+        let transaction = self.connection.transaction()?;
+        transaction.execute(&format!("DROP TABLE IF EXISTS {}", quote(table)), [])?;
+        transaction.execute(
+            &format!(
+                "CREATE TABLE {} (id INTEGER PRIMARY KEY{})",
+                quote(table),
+                names
+            ),
+            [],
+        )?;
+
+        {
+            let mut insert = transaction.prepare(&format!(
+                "INSERT INTO {} (id{}) VALUES (?1{})",
+                quote(table),
+                names,
+                parameters
+            ))?;
+
+            for index in 0..expected {
+                let mut row = Vec::with_capacity(columns.len() + 2);
+                row.push(ToSqlOutput::from(index as i64));
+                if let Some(generations) = generations {
+                    row.push(ToSqlOutput::from(generations[index]));
+                }
+                for (_, column) in columns {
+                    row.push(column.value(index)?);
+                }
+                insert.execute(params_from_iter(row.iter()))?;
+            }
+        }
+
+        transaction.commit()?;
+        Ok(())
+    }
+
+    /// Loads a column, checking that the ids form the contiguous range starting at zero.
+    pub fn load<Arena, T: FromSql>(
+        &self,
+        table: &str,
+        column: &str,
+    ) -> Result<Component<Arena, T>, SqliteError> {
+        self.load_values(table, column).map(Component::from)
+    }
+
+    pub fn load_raw<Arena, T: FromSql>(
+        &self,
+        table: &str,
+        column: &str,
+    ) -> Result<RawComponent<Arena, T>, SqliteError> {
+        self.load_values(table, column).map(RawComponent::from)
+    }
+
+    /// Loads the generations stored by [`save_with_generations`](Self::save_with_generations).
+    pub fn load_generations(&self, table: &str) -> Result<Vec<u32>, SqliteError> {
+        self.load_values(table, GENERATION)
+    }
+
+    fn load_values<T: FromSql>(&self, table: &str, column: &str) -> Result<Vec<T>, SqliteError> {
+        let mut select = self.connection.prepare(&format!(
+            "SELECT id, {} FROM {} ORDER BY id",
+            quote(column),
+            quote(table)
+        ))?;
+        let mut rows = select.query([])?;
+
+        let mut values = Vec::new();
+        while let Some(row) = rows.next()? {
+            let id: i64 = row.get(0)?;
+            if id != values.len() as i64 {
+                return Err(SqliteError::MissingId(values.len()));
+            }
+            values.push(row.get(1)?);
+        }
+
+        Ok(values)
+    }
+}
+
+#[inline]
+fn quote(identifier: &str) -> String {
+    format!("\"{}\"", identifier.replace('"', "\"\""))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_util::TempFile;
+
+    #[test]
+    fn round_trip() {
+        let path = TempFile::new("round_trip.sqlite");
+        let mass = Component::<(), f64>::from(vec![1.5, 2.5]);
+        let name = RawComponent::<(), String>::from(vec!["Sol".to_string(), "Vega".to_string()]);
+
+        let mut store = SqliteStore::open(&path.0).unwrap();
+        store
+            .save("stars", &[("mass", &mass), ("name", &name)])
+            .unwrap();
+        drop(store);
+
+        let store = SqliteStore::open(&path.0).unwrap();
+        let mass = store.load::<(), f64>("stars", "mass").unwrap();
+        let name = store.load_raw::<(), String>("stars", "name").unwrap();
+
+        assert_eq!(&[1.5, 2.5], mass.as_slice());
+        assert_eq!(&["Sol", "Vega"], name.as_slice());
+    }
+
+    #[test]
+    fn save_replaces_table() {
+        let path = TempFile::new("replace.sqlite");
+        let mut store = SqliteStore::open(&path.0).unwrap();
+
+        store
+            .save(
+                "stars",
+                &[("mass", &Component::<(), i64>::from(vec![1, 2]))],
+            )
+            .unwrap();
+        store
+            .save("stars", &[("mass", &Component::<(), i64>::from(vec![3]))])
+            .unwrap();
+
+        let mass = store.load::<(), i64>("stars", "mass").unwrap();
+        assert_eq!(&[3], mass.as_slice());
+    }
+
+    #[test]
+    fn save_checks_lengths() {
+        let path = TempFile::new("lengths.sqlite");
+        let mut store = SqliteStore::open(&path.0).unwrap();
+        let a = Component::<(), i64>::from(vec![1, 2]);
+        let b = Component::<(), i64>::from(vec![1]);
+
+        let error = store.save("t", &[("a", &a), ("b", &b)]).unwrap_err();
+
+        assert!(matches!(error, SqliteError::LengthMismatch(_)));
+    }
+
+    #[test]
+    fn load_rejects_gaps() {
+        let path = TempFile::new("gaps.sqlite");
+        let store = SqliteStore::open(&path.0).unwrap();
+        store
+            .connection
+            .execute_batch(
+                "CREATE TABLE t (id INTEGER PRIMARY KEY, a); INSERT INTO t VALUES (1, 5);",
+            )
+            .unwrap();
+
+        let error = store.load::<(), i64>("t", "a").unwrap_err();
+
+        assert!(matches!(error, SqliteError::MissingId(0)));
+    }
+
+    #[test]
+    fn generations_round_trip() {
+        let path = TempFile::new("generations.sqlite");
+        let mass = Component::<(), f64>::from(vec![1.5, 2.5]);
+        let mut store = SqliteStore::open(&path).unwrap();
+
+        store
+            .save_with_generations("stars", &[0, 3], &[("mass", &mass)])
+            .unwrap();
+
+        assert_eq!(vec![0, 3], store.load_generations("stars").unwrap());
+        assert_eq!(
+            &[1.5, 2.5],
+            store.load::<(), f64>("stars", "mass").unwrap().as_slice()
+        );
+        assert!(matches!(
+            store.save_with_generations("stars", &[0], &[("mass", &mass)]),
+            Err(SqliteError::LengthMismatch(LengthMismatch {
+                column: "generation",
+                ..
+            }))
+        ));
+    }
+}
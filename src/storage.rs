@@ -0,0 +1,42 @@
+use std::ops::IndexMut;
+
+/// The backing store of a component: a growable sequence of values indexed by id index.
+///
+/// `UntypedComponent`, `RawComponent` and `Component` are generic over their storage and default to
+/// [`IndexVec`](crate::IndexVec). Methods that hand out slices, such as indexing by id range, are only
+/// available on the default storage.
+pub trait Storage<T>: Default + IndexMut<usize, Output = T> {
+    type Iter<'a>: Iterator<Item = &'a T>
+    where
+        Self: 'a,
+        T: 'a;
+
+    type IterMut<'a>: Iterator<Item = &'a mut T>
+    where
+        Self: 'a,
+        T: 'a;
+
+    /// Sets the value at `index`, first extending the storage with `fill` if it is too short.
+    fn insert_with<F: Fn() -> T>(&mut self, index: usize, value: T, fill: F);
+
+    fn get(&self, index: usize) -> Option<&T>;
+
+    fn get_mut(&mut self, index: usize) -> Option<&mut T>;
+
+    fn len(&self) -> usize;
+
+    #[inline]
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn swap(&mut self, a: usize, b: usize);
+
+    fn resize_with<F: FnMut() -> T>(&mut self, len: usize, f: F);
+
+    fn fill_with<F: FnMut() -> T>(&mut self, f: F);
+
+    fn iter(&self) -> Self::Iter<'_>;
+
+    fn iter_mut(&mut self) -> Self::IterMut<'_>;
+}
@@ -0,0 +1,170 @@
+use crate::component::Component;
+use crate::id_set::IdSet;
+use gen_id_allocator::{Id, ValidId};
+use std::marker::PhantomData;
+
+/// A marker on a subset of ids, such as `Selected` or `Destroyed`, stored as a membership set.
+#[derive(Debug)]
+pub struct Tag<Arena, Marker> {
+    ids: IdSet<Arena>,
+    marker: PhantomData<*const Marker>,
+}
+
+impl<Arena, Marker> Default for Tag<Arena, Marker> {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            ids: IdSet::default(),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<Arena, Marker> Clone for Tag<Arena, Marker> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            ids: self.ids.clone(),
+            marker: PhantomData,
+        }
+    }
+
+    #[inline]
+    fn clone_from(&mut self, source: &Self) {
+        self.ids.clone_from(&source.ids);
+    }
+}
+
+impl<Arena, Marker> Tag<Arena, Marker> {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Tags an id, returning `true` if it was not already tagged.
+    #[inline]
+    pub fn add<Id: ValidId<Arena = Arena>>(&mut self, id: Id) -> bool {
+        self.ids.insert(id)
+    }
+
+    /// Untags an id, returning `true` if it was tagged.
+    #[inline]
+    pub fn remove<Id: ValidId<Arena = Arena>>(&mut self, id: Id) -> bool {
+        self.ids.remove(id)
+    }
+
+    #[inline]
+    pub fn has<Id: ValidId<Arena = Arena>>(&self, id: Id) -> bool {
+        self.ids.contains(id)
+    }
+
+    #[inline]
+    pub fn count(&self) -> usize {
+        self.ids.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+
+    #[inline]
+    pub fn clear(&mut self) {
+        self.ids.clear();
+    }
+
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = Id<Arena>> + '_ {
+        self.ids.iter()
+    }
+
+    #[inline]
+    pub fn ids(&self) -> &IdSet<Arena> {
+        &self.ids
+    }
+
+    /// The tagged ids in `component`, together with their values.
+    #[inline]
+    pub fn filter<'a, T>(
+        &'a self,
+        component: &'a Component<Arena, T>,
+    ) -> impl Iterator<Item = (Id<Arena>, &'a T)> {
+        component.iter_in(&self.ids)
+    }
+
+    #[inline]
+    pub fn filter_mut<'a, T>(
+        &'a self,
+        component: &'a mut Component<Arena, T>,
+    ) -> impl Iterator<Item = (Id<Arena>, &'a mut T)> {
+        component.iter_mut_in(&self.ids)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_util::valid;
+
+    struct Selected;
+
+    #[test]
+    fn add_and_remove() {
+        let mut tag = Tag::<(), Selected>::new();
+
+        assert!(tag.add(valid(2)));
+        assert!(!tag.add(valid(2)));
+        assert!(tag.has(valid(2)));
+        assert!(!tag.has(valid(1)));
+        assert_eq!(1, tag.count());
+
+        assert!(!tag.remove(valid(1)));
+        assert!(tag.remove(valid(2)));
+        assert!(!tag.remove(valid(2)));
+        assert!(tag.is_empty());
+    }
+
+    #[test]
+    fn re_add_after_remove() {
+        let mut tag = Tag::<(), Selected>::new();
+        tag.add(valid(0));
+        tag.remove(valid(0));
+
+        assert!(tag.add(valid(0)));
+        assert!(tag.has(valid(0)));
+        assert_eq!(1, tag.count());
+    }
+
+    #[test]
+    fn iter_and_clear() {
+        let mut tag = Tag::<(), Selected>::new();
+        tag.add(valid(3));
+        tag.add(valid(1));
+
+        assert_eq!(
+            vec![Id::first(1), Id::first(3)],
+            tag.iter().collect::<Vec<_>>()
+        );
+
+        tag.clear();
+
+        assert_eq!(0, tag.iter().count());
+    }
+
+    #[test]
+    fn filter_tagged_values() {
+        let mut tag = Tag::<(), Selected>::new();
+        let mut component = Component::<(), u32>::from(vec![1, 2, 3]);
+        tag.add(valid(0));
+        tag.add(valid(2));
+
+        tag.filter_mut(&mut component)
+            .for_each(|(_, value)| *value += 10);
+
+        assert_eq!(
+            vec![(Id::first(0), &11), (Id::first(2), &13)],
+            tag.filter(&component).collect::<Vec<_>>()
+        );
+        assert_eq!(Some(&2), component.get(valid(1)));
+    }
+}
@@ -0,0 +1,37 @@
+use crate::component::Component;
+use gen_id_allocator::{Id, Valid};
+
+pub fn valid<Arena>(index: usize) -> Valid<'static, Id<Arena>> {
+    Valid::assert(Id::first(index))
+}
+
+pub fn values<Arena, T: Copy>(component: &Component<Arena, T>) -> Vec<T> {
+    component.into_iter().copied().collect()
+}
+
+/// A path in the temporary directory that is removed when dropped, even if the test fails.
+#[cfg(any(feature = "mmap", feature = "sqlite"))]
+pub struct TempFile(pub std::path::PathBuf);
+
+#[cfg(any(feature = "mmap", feature = "sqlite"))]
+impl TempFile {
+    pub fn new(name: &str) -> Self {
+        let file = format!("gen_id_component_{}_{}", std::process::id(), name);
+        Self(std::env::temp_dir().join(file))
+    }
+}
+
+#[cfg(any(feature = "mmap", feature = "sqlite"))]
+impl AsRef<std::path::Path> for TempFile {
+    #[inline]
+    fn as_ref(&self) -> &std::path::Path {
+        &self.0
+    }
+}
+
+#[cfg(any(feature = "mmap", feature = "sqlite"))]
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        std::fs::remove_file(&self.0).ok();
+    }
+}
@@ -1,28 +1,33 @@
 use crate::index_vec::IndexVec;
+use crate::storage::Storage;
 use gen_id_allocator::untyped::{UntypedId, UntypedIdRange};
 use ref_cast::RefCast;
+use std::marker::PhantomData;
 use std::ops::{Index, IndexMut};
 
 #[repr(transparent)]
 #[derive(Debug, RefCast)]
-pub struct UntypedComponent<T> {
-    values: IndexVec<T>,
+pub struct UntypedComponent<T, S = IndexVec<T>> {
+    values: S,
+    marker: PhantomData<T>,
 }
 
-impl<T> Default for UntypedComponent<T> {
+impl<T, S: Default> Default for UntypedComponent<T, S> {
     #[inline]
     fn default() -> Self {
         Self {
             values: Default::default(),
+            marker: PhantomData,
         }
     }
 }
 
-impl<T: Clone> Clone for UntypedComponent<T> {
+impl<T, S: Clone> Clone for UntypedComponent<T, S> {
     #[inline]
     fn clone(&self) -> Self {
         Self {
             values: self.values.clone(),
+            marker: PhantomData,
         }
     }
 
@@ -32,14 +37,14 @@ impl<T: Clone> Clone for UntypedComponent<T> {
     }
 }
 
-impl<T: Default> UntypedComponent<T> {
+impl<T: Default, S: Storage<T>> UntypedComponent<T, S> {
     #[inline]
     pub fn insert(&mut self, id: UntypedId, value: T) {
         self.insert_with(id, value, Default::default);
     }
 }
 
-impl<T> UntypedComponent<T> {
+impl<T, S: Storage<T>> UntypedComponent<T, S> {
     #[inline]
     pub fn insert_with<F: Fn() -> T>(&mut self, id: UntypedId, value: T, fill: F) {
         self.values.insert_with(id.index(), value, fill);
@@ -60,18 +65,23 @@ impl<T> UntypedComponent<T> {
         self.values.swap(a.index(), b.index());
     }
 
+    #[inline]
+    pub fn resize_with<F: FnMut() -> T>(&mut self, len: usize, f: F) {
+        self.values.resize_with(len, f);
+    }
+
     #[inline]
     pub fn fill_with<F: FnMut() -> T>(&mut self, f: F) {
         self.values.fill_with(f);
     }
 
     #[inline]
-    pub fn iter(&self) -> std::slice::Iter<T> {
+    pub fn iter(&self) -> S::Iter<'_> {
         self.values.iter()
     }
 
     #[inline]
-    pub fn iter_mut(&mut self) -> std::slice::IterMut<T> {
+    pub fn iter_mut(&mut self) -> S::IterMut<'_> {
         self.values.iter_mut()
     }
 
@@ -84,13 +94,58 @@ impl<T> UntypedComponent<T> {
     pub fn is_empty(&self) -> bool {
         self.len() == 0
     }
+
+    #[inline]
+    pub(crate) fn storage(&self) -> &S {
+        &self.values
+    }
+
+    #[inline]
+    pub(crate) fn storage_mut(&mut self) -> &mut S {
+        &mut self.values
+    }
+}
+
+impl<T> UntypedComponent<T> {
+    #[inline]
+    pub(crate) fn as_slice(&self) -> &[T] {
+        self.values.as_slice()
+    }
+
+    #[inline]
+    pub(crate) fn as_mut_slice(&mut self) -> &mut [T] {
+        self.values.as_mut_slice()
+    }
+
+    /// Resizes the component, returning `false` instead of aborting if the memory is not available.
+    #[cfg(feature = "ffi")]
+    #[inline]
+    pub(crate) fn try_resize(&mut self, len: usize) -> bool
+    where
+        T: Default,
+    {
+        let additional = len.saturating_sub(self.len());
+        if self.values.try_reserve(additional).is_err() {
+            return false;
+        }
+        self.resize_with(len, Default::default);
+        true
+    }
 }
 
 impl<T> From<Vec<T>> for UntypedComponent<T> {
     #[inline]
     fn from(values: Vec<T>) -> Self {
+        IndexVec::from(values).into()
+    }
+}
+
+impl<T, S: Storage<T>> From<S> for UntypedComponent<T, S> {
+    #[inline]
+    fn from(values: S) -> Self {
         UntypedComponent {
-            values: values.into(),
+            values,
+            marker: PhantomData,
         }
     }
 }
@@ -102,9 +157,9 @@ impl<T> From<UntypedComponent<T>> for Vec<T> {
     }
 }
 
-impl<'a, T> IntoIterator for &'a UntypedComponent<T> {
+impl<'a, T, S: Storage<T>> IntoIterator for &'a UntypedComponent<T, S> {
     type Item = &'a T;
-    type IntoIter = std::slice::Iter<'a, T>;
+    type IntoIter = S::Iter<'a>;
 
     #[inline]
     fn into_iter(self) -> Self::IntoIter {
@@ -112,9 +167,9 @@ impl<'a, T> IntoIterator for &'a UntypedComponent<T> {
     }
 }
 
-impl<'a, T> IntoIterator for &'a mut UntypedComponent<T> {
+impl<'a, T, S: Storage<T>> IntoIterator for &'a mut UntypedComponent<T, S> {
     type Item = &'a mut T;
-    type IntoIter = std::slice::IterMut<'a, T>;
+    type IntoIter = S::IterMut<'a>;
 
     #[inline]
     fn into_iter(self) -> Self::IntoIter {
@@ -122,7 +177,7 @@ impl<'a, T> IntoIterator for &'a mut UntypedComponent<T> {
     }
 }
 
-impl<T> Index<UntypedId> for UntypedComponent<T> {
+impl<T, S: Storage<T>> Index<UntypedId> for UntypedComponent<T, S> {
     type Output = T;
 
     #[inline]
@@ -131,14 +186,14 @@ impl<T> Index<UntypedId> for UntypedComponent<T> {
     }
 }
 
-impl<T> IndexMut<UntypedId> for UntypedComponent<T> {
+impl<T, S: Storage<T>> IndexMut<UntypedId> for UntypedComponent<T, S> {
     #[inline]
     fn index_mut(&mut self, index: UntypedId) -> &mut Self::Output {
         self.values.index_mut(index.index())
     }
 }
 
-impl<T> Index<&UntypedId> for UntypedComponent<T> {
+impl<T, S: Storage<T>> Index<&UntypedId> for UntypedComponent<T, S> {
     type Output = T;
 
     #[inline]
@@ -147,7 +202,7 @@ impl<T> Index<&UntypedId> for UntypedComponent<T> {
     }
 }
 
-impl<T> IndexMut<&UntypedId> for UntypedComponent<T> {
+impl<T, S: Storage<T>> IndexMut<&UntypedId> for UntypedComponent<T, S> {
     #[inline]
     fn index_mut(&mut self, index: &UntypedId) -> &mut Self::Output {
         self.values.index_mut(index.index())